@@ -0,0 +1,62 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use solutions::twenty_three::day07::{Game, RuleSet};
+
+const HANDS: usize = 1_000_000;
+const CARDS: &[u8] = b"23456789TJQKA";
+
+/// Generates hands and bids with a linear congruential generator, so that
+/// every run ranks the same input.
+fn generate_input(hands: usize) -> String {
+    let mut state: u64 = 0x2023_0007;
+    let mut next = || {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+
+        (state >> 33) as usize
+    };
+
+    let mut input = String::new();
+
+    for _ in 0..hands {
+        for _ in 0..5 {
+            input.push(CARDS[next() % CARDS.len()] as char);
+        }
+
+        input.push_str(&format!(" {}\n", next() % 1000 + 1));
+    }
+
+    input
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let input = generate_input(HANDS);
+    let game = Game::parse(&input, RuleSet::jokers()).unwrap();
+    let rules = RuleSet::jokers();
+
+    c.bench_function(
+        "year2023day07rank_packed",
+        |b| {
+            b.iter(|| black_box(&game).winnings())
+        }
+    );
+
+    c.bench_function(
+        "year2023day07rank_compare",
+        |b| {
+            b.iter(|| {
+                let hands = black_box(&game).hands();
+                let mut ranking: Vec<_> = hands.iter().collect();
+                ranking.sort_by(|l, r| rules.compare(l, r));
+                ranking
+            })
+        }
+    );
+}
+
+criterion_group!{
+    name = benches;
+    config = Criterion::default().significance_level(0.1).sample_size(10);
+    targets = criterion_benchmark
+}
+criterion_main!(benches);
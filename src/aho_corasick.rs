@@ -0,0 +1,214 @@
+use std::collections::VecDeque;
+
+const ALPHABET: usize = 256;
+const NONE: usize = usize::MAX;
+
+/// An occurrence of pattern number `pattern` at `start..end` in a haystack.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Match {
+    pub pattern: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// An Aho-Corasick automaton over bytes, finding all occurrences of a set of
+/// patterns in a single pass over the haystack.
+struct Automaton {
+    transitions: Vec<[usize; ALPHABET]>,
+    outputs: Vec<Vec<usize>>,
+    lengths: Vec<usize>,
+}
+
+impl Automaton {
+    fn new(patterns: &[Vec<u8>]) -> Self {
+        let mut transitions = vec!([NONE; ALPHABET]);
+        let mut outputs = vec!(vec!());
+        let mut lengths = vec!();
+
+        // build the trie of all patterns
+        for (id, pattern) in patterns.iter().enumerate() {
+            let mut state = 0;
+
+            for b in pattern.iter().copied() {
+                if transitions[state][b as usize] == NONE {
+                    transitions[state][b as usize] = transitions.len();
+                    transitions.push([NONE; ALPHABET]);
+                    outputs.push(vec!());
+                }
+
+                state = transitions[state][b as usize];
+            }
+
+            outputs[state].push(id);
+            lengths.push(pattern.len());
+        }
+
+        // add failure transitions breadth-first, so that the failure state of
+        // every state is complete by the time the state itself is visited
+        let mut fail = vec![0; transitions.len()];
+        let mut queue = VecDeque::from([0]);
+
+        while let Some(state) = queue.pop_front() {
+            let fail_row = transitions[fail[state]];
+
+            for (b, child) in transitions[state].iter_mut().enumerate() {
+                let fallback = match state {
+                    0 => 0,
+                    _ => fail_row[b],
+                };
+
+                if *child == NONE {
+                    *child = fallback;
+                    continue;
+                }
+
+                fail[*child] = fallback;
+
+                let inherited = outputs[fallback].clone();
+                outputs[*child].extend(inherited);
+
+                queue.push_back(*child);
+            }
+        }
+
+        Self { transitions, outputs, lengths }
+    }
+
+    fn max_len(&self) -> usize {
+        self.lengths.iter().copied().max().unwrap_or(0)
+    }
+
+    /// Returns all (possibly overlapping) matches in the haystack, ordered by
+    /// end position.
+    fn find_iter<'a, I>(
+        &'a self,
+        haystack: I,
+    ) -> impl Iterator<Item = Match> + 'a where I: Iterator<Item = u8> + 'a {
+
+        let mut state = 0;
+
+        haystack
+            .enumerate()
+            .flat_map(move |(i, b)| {
+                state = self.transitions[state][b as usize];
+
+                self.outputs[state].iter().map(move |pattern| Match {
+                    pattern: *pattern,
+                    start: i + 1 - self.lengths[*pattern],
+                    end: i + 1,
+                })
+            })
+    }
+
+    /// Returns the match that starts first, preferring the longest match
+    /// in case of a tie. Only scans as far as a better match could end.
+    fn leftmost<I>(&self, haystack: I) -> Option<Match>
+        where I: Iterator<Item = u8> {
+
+        let max_len = self.max_len();
+        let mut best: Option<Match> = None;
+
+        for m in self.find_iter(haystack) {
+            if let Some(b) = best {
+                if m.end > b.start + max_len {
+                    break;
+                }
+            }
+
+            let better = best.is_none_or(|b| {
+                m.start < b.start || (m.start == b.start && m.end > b.end)
+            });
+
+            if better {
+                best = Some(m);
+            }
+        }
+
+        best
+    }
+}
+
+/// Finds occurrences of a fixed set of byte patterns. Keeps one automaton
+/// for the patterns and one for the reversed patterns, so that both the
+/// first and the last match in a haystack can be found without scanning
+/// all of it.
+pub struct Matcher {
+    forward: Automaton,
+    backward: Automaton,
+}
+
+impl Matcher {
+    pub fn new(patterns: &[&[u8]]) -> Self {
+        let forward: Vec<_> = patterns
+            .iter()
+            .map(|p| p.to_vec())
+            .collect();
+
+        let backward: Vec<_> = patterns
+            .iter()
+            .map(|p| p.iter().rev().copied().collect())
+            .collect();
+
+        let (forward, backward) = (
+            Automaton::new(&forward),
+            Automaton::new(&backward),
+        );
+
+        Self { forward, backward }
+    }
+
+    /// Returns all matches in the haystack, including overlapping ones such
+    /// as both "eight" and "two" in "eightwo".
+    pub fn matches(&self, haystack: &[u8]) -> Vec<Match> {
+        self.forward.find_iter(haystack.iter().copied()).collect()
+    }
+
+    pub fn first(&self, haystack: &[u8]) -> Option<Match> {
+        self.forward.leftmost(haystack.iter().copied())
+    }
+
+    pub fn last(&self, haystack: &[u8]) -> Option<Match> {
+        let m = self.backward.leftmost(haystack.iter().rev().copied())?;
+
+        Some(Match {
+            pattern: m.pattern,
+            start: haystack.len() - m.end,
+            end: haystack.len() - m.start,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Match, Matcher};
+
+    const PATTERNS: [&[u8]; 4] = [b"eight", b"two", b"abcd", b"bc"];
+
+    #[test]
+    fn test_matches() {
+        let matcher = Matcher::new(&PATTERNS);
+
+        assert_eq!(matcher.matches(b"xeightwo"), vec!(
+            Match { pattern: 0, start: 1, end: 6 },
+            Match { pattern: 1, start: 5, end: 8 },
+        ));
+    }
+
+    #[test]
+    fn test_first_last() {
+        let matcher = Matcher::new(&PATTERNS);
+
+        // "bc" ends first, but "abcd" starts first
+        assert_eq!(
+            matcher.first(b"abcdtwo"),
+            Some(Match { pattern: 2, start: 0, end: 4 })
+        );
+
+        assert_eq!(
+            matcher.last(b"eightwo"),
+            Some(Match { pattern: 1, start: 4, end: 7 })
+        );
+
+        assert_eq!(matcher.first(b"none"), None);
+    }
+}
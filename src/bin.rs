@@ -1,19 +1,29 @@
 use std::env;
+use solutions::twenty_three::day08;
 
 fn print_usage() {
-    eprintln!("Usage: aoc year day");
+    eprintln!("Usage: aoc year day [--dot | --path]");
+    eprintln!("  --dot   print the 2023 day 8 network in DOT format");
+    eprintln!("  --path  print the 2023 day 8 path from AAA to ZZZ");
+}
+
+/// What to print instead of the solutions.
+enum Export {
+    Dot,
+    Path,
 }
 
 struct Arguments {
     year: u16,
     day: u8,
+    export: Option<Export>,
 }
 
 impl Arguments {
     fn parse(args: env::Args) -> Arguments {
         let args: Vec<_> = args.skip(1).collect();
 
-        if args.len() != 2 {
+        if args.len() != 2 && args.len() != 3 {
             eprintln!("Incorrect number of arguments");
             print_usage();
             std::process::exit(1);
@@ -22,7 +32,23 @@ impl Arguments {
         let year = args[0].parse::<u16>().expect("Could not parse year");
         let day = args[1].parse::<u8>().expect("Could not parse day");
 
-        Arguments { year, day }
+        let export = match args.get(2).map(|flag| flag.as_str()) {
+            None => None,
+            Some("--dot") => Some(Export::Dot),
+            Some("--path") => Some(Export::Path),
+            Some(flag) => {
+                eprintln!("Unknown flag {}", flag);
+                print_usage();
+                std::process::exit(1);
+            },
+        };
+
+        if export.is_some() && (year, day) != (2023, 8) {
+            eprintln!("Exports are only available for 2023 day 8");
+            std::process::exit(1);
+        }
+
+        Arguments { year, day, export }
     }
 }
 
@@ -35,6 +61,22 @@ fn main() {
     let client = rudolf_rs::Client::new(session_key);
     let input = client.get(args.year, args.day).unwrap();
 
+    if let Some(export) = args.export {
+        let desert_map = day08::DesertMap::try_from(input.as_str())
+            .expect("Could not parse map");
+
+        let path = desert_map
+            .path(day08::START, |node| node == day08::END)
+            .expect("No path from start to end");
+
+        match export {
+            Export::Dot => print!("{}", desert_map.to_dot(&path)),
+            Export::Path => println!("{}", path.join(" -> ")),
+        }
+
+        return;
+    }
+
     let solution = solutions::get_solution(
         args.year as usize,
         args.day as usize
@@ -42,5 +84,16 @@ fn main() {
 
     println!("{}", solution.part1(&input));
     println!("{}", solution.part2(&input));
+
+    // part 2 of day 8 only solves the general case with a slower method
+    if (args.year, args.day) == (2023, 8) {
+        let ghosts = day08::DesertMap::try_from(input.as_str())
+            .map(|desert_map| desert_map.ghosts());
+
+        if ghosts.is_ok_and(|ghosts| !ghosts.lcm_shortcut) {
+            eprintln!("warning: ghosts do not arrive at regular intervals, \
+                falling back to the Chinese remainder theorem");
+        }
+    }
 }
 
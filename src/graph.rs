@@ -0,0 +1,115 @@
+use std::collections::{HashMap, VecDeque};
+
+/// An undirected graph with unit-capacity edges between nodes `0..len`.
+pub struct Graph {
+    adjacency: Vec<Vec<usize>>,
+}
+
+impl Graph {
+    pub fn new(len: usize) -> Self {
+        Self { adjacency: vec![vec!(); len] }
+    }
+
+    pub fn len(&self) -> usize {
+        self.adjacency.len()
+    }
+
+    pub fn add_edge(&mut self, a: usize, b: usize) {
+        self.adjacency[a].push(b);
+        self.adjacency[b].push(a);
+    }
+
+    /// Finds the shortest path from `source` to `sink` in the residual graph
+    /// by BFS. Also returns which nodes are reachable from `source`.
+    fn augmenting_path(
+        &self,
+        flow: &HashMap<(usize, usize), isize>,
+        source: usize,
+        sink: usize,
+    ) -> (Option<Vec<usize>>, Vec<bool>) {
+        let mut prev = vec![usize::MAX; self.len()];
+        let mut reached = vec![false; self.len()];
+        let mut queue = VecDeque::from([source]);
+
+        reached[source] = true;
+
+        while let Some(node) = queue.pop_front() {
+            for next in &self.adjacency[node] {
+                let residual = 1 - flow.get(&(node, *next)).unwrap_or(&0);
+
+                if residual > 0 && !reached[*next] {
+                    reached[*next] = true;
+                    prev[*next] = node;
+                    queue.push_back(*next);
+                }
+            }
+        }
+
+        if !reached[sink] {
+            return (None, reached);
+        }
+
+        let mut path = vec!(sink);
+
+        while path[path.len() - 1] != source {
+            path.push(prev[path[path.len() - 1]]);
+        }
+
+        path.reverse();
+
+        (Some(path), reached)
+    }
+
+    /// Returns the size of a minimum edge cut separating `source` from
+    /// `sink`, along with the nodes on the source side of it. Uses
+    /// Edmonds–Karp, which stops early with `None` once the cut is known to
+    /// be larger than `limit`.
+    pub fn min_cut(
+        &self,
+        source: usize,
+        sink: usize,
+        limit: usize,
+    ) -> Option<(usize, Vec<usize>)> {
+        let mut flow: HashMap<(usize, usize), isize> = HashMap::new();
+        let mut total = 0;
+
+        loop {
+            let (path, reached) = self.augmenting_path(&flow, source, sink);
+
+            let Some(path) = path else {
+                let side = (0..self.len()).filter(|n| reached[*n]).collect();
+
+                return Some((total, side));
+            };
+
+            if total == limit {
+                return None;
+            }
+
+            for pair in path.windows(2) {
+                *flow.entry((pair[0], pair[1])).or_insert(0) += 1;
+                *flow.entry((pair[1], pair[0])).or_insert(0) -= 1;
+            }
+
+            total += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Graph;
+
+    #[test]
+    fn test_min_cut() {
+        // two triangles joined by a single bridge
+        let mut graph = Graph::new(6);
+
+        for (a, b) in [(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3), (2, 3)] {
+            graph.add_edge(a, b);
+        }
+
+        assert_eq!(graph.min_cut(0, 5, 3), Some((1, vec!(0, 1, 2))));
+        assert_eq!(graph.min_cut(0, 1, 1), None);
+    }
+}
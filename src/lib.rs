@@ -1,6 +1,10 @@
 use std::fmt::Display;
 
-mod twenty_three;
+mod aho_corasick;
+mod graph;
+mod pathfinding;
+mod polygon;
+pub mod twenty_three;
 
 pub trait Solver {
     fn part1(&self, input: &str) -> Box<dyn Display>;
@@ -0,0 +1,129 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A monotone priority queue for small integer priorities (Dial's
+/// algorithm). Items are kept in one bucket per priority and popped in
+/// ascending order. Pushing an item with a priority lower than the last
+/// popped one is not supported, which always holds for Dijkstra's algorithm
+/// with non-negative edge weights.
+pub struct BucketQueue<T> {
+    buckets: Vec<Vec<T>>,
+    cursor: usize,
+    len: usize,
+}
+
+impl<T> BucketQueue<T> {
+    pub fn new() -> Self {
+        Self { buckets: vec!(), cursor: 0, len: 0 }
+    }
+
+    pub fn push(&mut self, priority: usize, item: T) {
+        assert!(priority >= self.cursor, "Priority below queue cursor");
+
+        if priority >= self.buckets.len() {
+            self.buckets.resize_with(priority + 1, Vec::new);
+        }
+
+        self.buckets[priority].push(item);
+        self.len += 1;
+    }
+
+    pub fn pop(&mut self) -> Option<(usize, T)> {
+        if self.len == 0 {
+            return None;
+        }
+
+        while self.buckets[self.cursor].is_empty() {
+            self.cursor += 1;
+        }
+
+        self.len -= 1;
+
+        self.buckets[self.cursor].pop().map(|item| (self.cursor, item))
+    }
+}
+
+impl<T> Default for BucketQueue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the cost of the cheapest path from any of the start states to a
+/// state satisfying `goal`, or `None` if no such state is reachable. States
+/// are arbitrary user-defined values; `neighbours` yields each successor
+/// together with the (non-negative) cost of moving to it.
+pub fn dijkstra<S, I, N, E, G>(
+    starts: I,
+    neighbours: N,
+    goal: G,
+) -> Option<usize>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+    N: Fn(&S) -> E,
+    E: IntoIterator<Item = (S, usize)>,
+    G: Fn(&S) -> bool,
+{
+    let mut costs: HashMap<S, usize> = HashMap::new();
+    let mut queue = BucketQueue::new();
+
+    for start in starts {
+        costs.insert(start.clone(), 0);
+        queue.push(0, start);
+    }
+
+    while let Some((cost, state)) = queue.pop() {
+        // skip stale queue entries that have since been improved upon
+        if costs.get(&state).is_some_and(|best| *best < cost) {
+            continue;
+        }
+
+        if goal(&state) {
+            return Some(cost);
+        }
+
+        for (next, step) in neighbours(&state) {
+            let next_cost = cost + step;
+
+            if costs.get(&next).is_none_or(|best| next_cost < *best) {
+                costs.insert(next.clone(), next_cost);
+                queue.push(next_cost, next);
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{dijkstra, BucketQueue};
+
+    #[test]
+    fn test_bucket_queue() {
+        let mut queue = BucketQueue::new();
+
+        queue.push(3, 'c');
+        queue.push(1, 'a');
+        queue.push(2, 'b');
+
+        assert_eq!(queue.pop(), Some((1, 'a')));
+        assert_eq!(queue.pop(), Some((2, 'b')));
+        assert_eq!(queue.pop(), Some((3, 'c')));
+        assert_eq!(queue.pop(), None);
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        // walk along the number line, where stepping by 3 is cheaper per unit
+        let cost = dijkstra(
+            [0],
+            |n: &i32| [(n + 1, 2), (n + 3, 3)],
+            |n| *n == 7,
+        );
+
+        assert_eq!(cost, Some(8));
+        assert_eq!(dijkstra([0], |_: &i32| [], |n| *n == 1), None);
+    }
+}
@@ -0,0 +1,75 @@
+use num::integer::gcd;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Point {
+    pub x: i64,
+    pub y: i64,
+}
+
+/// A simple (non-self-intersecting) polygon on the integer lattice, given by
+/// its vertices in either winding order.
+pub struct Polygon {
+    vertices: Vec<Point>,
+}
+
+impl Polygon {
+    pub fn new(vertices: Vec<Point>) -> Self {
+        Self { vertices }
+    }
+
+    fn edges(&self) -> impl Iterator<Item = (&Point, &Point)> {
+        self
+            .vertices
+            .iter()
+            .zip(self.vertices.iter().cycle().skip(1))
+    }
+
+    /// Returns twice the enclosed area, as given by the shoelace formula.
+    /// Doubling keeps the result integral for any lattice polygon.
+    pub fn double_area(&self) -> i64 {
+        self
+            .edges()
+            .map(|(a, b)| a.x * b.y - b.x * a.y)
+            .sum::<i64>()
+            .abs()
+    }
+
+    /// Returns the number of lattice points on the polygon's boundary.
+    pub fn boundary_points(&self) -> i64 {
+        self
+            .edges()
+            .map(|(a, b)| gcd(b.x - a.x, b.y - a.y))
+            .sum()
+    }
+
+    /// Returns the number of lattice points strictly inside the polygon,
+    /// using Pick's theorem: A = i + b/2 - 1.
+    pub fn interior_points(&self) -> i64 {
+        (self.double_area() - self.boundary_points()) / 2 + 1
+    }
+
+    /// Returns the number of lattice points inside or on the polygon.
+    pub fn enclosed_points(&self) -> i64 {
+        self.interior_points() + self.boundary_points()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Point, Polygon};
+
+    #[test]
+    fn test_square() {
+        let square = Polygon::new(vec!(
+            Point { x: 0, y: 0 },
+            Point { x: 4, y: 0 },
+            Point { x: 4, y: 4 },
+            Point { x: 0, y: 4 },
+        ));
+
+        assert_eq!(square.double_area(), 32);
+        assert_eq!(square.boundary_points(), 16);
+        assert_eq!(square.interior_points(), 9);
+        assert_eq!(square.enclosed_points(), 25);
+    }
+}
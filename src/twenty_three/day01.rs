@@ -1,106 +1,127 @@
 use crate::Solution;
-use std::collections::hash_map::HashMap;
+use crate::aho_corasick::{Match, Matcher};
 
 pub const SOLUTION: Solution<usize, usize> = Solution { part1, part2 };
 
-/// Returns the sum of all calibration values. Simple forward and backward
-/// scanning to find the first and last digit in each line respectively.
-fn part1(input: &str) -> usize {
-    let mut sum = 0;
-    
-    for line in input.lines() {
-        for c in line.chars() {
-            if let Some(digit) = c.to_digit(10) {
-                sum += digit as usize * 10;
-                break;
-            }
-        }
+/// Plain digits, as understood by part 1.
+pub const DIGITS: [(&str, usize); 10] = [
+    ("0", 0), ("1", 1), ("2", 2), ("3", 3), ("4", 4),
+    ("5", 5), ("6", 6), ("7", 7), ("8", 8), ("9", 9),
+];
 
-        for c in line.chars().rev() {
-            if let Some(digit) = c.to_digit(10) {
-                sum += digit as usize;
-                break;
-            }
-        }
-    }
+/// Digits spelled out in English, which part 2 accepts as well.
+pub const ENGLISH: [(&str, usize); 10] = [
+    ("zero", 0), ("one", 1), ("two", 2), ("three", 3), ("four", 4),
+    ("five", 5), ("six", 6), ("seven", 7), ("eight", 8), ("nine", 9),
+];
 
-    sum
+/// A token found in a line of the calibration document.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Token<'a> {
+    pub text: &'a str,
+    pub start: usize,
+    pub value: usize,
 }
 
-#[derive(PartialEq)]
-enum Direction {
-    Forward,
-    Backward
+/// The first and last token of a line, which together make up its
+/// calibration value.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Calibration<'a> {
+    pub first: Token<'a>,
+    pub last: Token<'a>,
 }
 
-use Direction::*;
-
-/// Find the first instance in the given direction of an element in a set of
-/// needles in a haystack.
-fn find<'a>(
-    haystack: &'a [u8],
-    needles: &'a [&'a [u8]],
-    dir: Direction,
-) -> Option<&'a [u8]> {
-    let mut cursor = haystack;
-
-    while !cursor.is_empty() {
-        for needle in needles {
-            if (dir == Forward && cursor.starts_with(needle)) ||
-               (dir == Backward && cursor.ends_with(needle)) {
-                return Some(needle);
-            }
-        }
-
-        match dir {
-            Forward => cursor = &cursor[1..],
-            Backward => cursor = &cursor[..cursor.len() - 1],
-        }
+impl Calibration<'_> {
+    pub fn value(&self) -> usize {
+        self.first.value * 10 + self.last.value
     }
+}
 
-    None
+/// The set of lexemes we consider to be valid representations of digits in
+/// our target language, each mapped to the digit it represents. Lexemes may
+/// be anything from digits and words to Roman numerals.
+pub struct Lexicon {
+    values: Vec<usize>,
+    matcher: Matcher,
 }
 
-/// These values are what we consider to be valid representations of digits in
-/// our target language.
-const LEXEMES: [&[u8]; 20] = [
-    b"0", b"1", b"2", b"3", b"4", b"5", b"6", b"7", b"8", b"9", b"zero", b"one",
-    b"two", b"three", b"four", b"five", b"six", b"seven", b"eight", b"nine",
-];
+impl Lexicon {
+    pub fn new(entries: &[(&str, usize)]) -> Self {
+        let lexemes: Vec<_> = entries
+            .iter()
+            .map(|(lexeme, _)| lexeme.as_bytes())
+            .collect();
 
-/// Returns the sum of all calibration values, where digits may be given as
-/// simple numbers or spelled out as letters. Scans in forward and backward
-/// direction to find the first and last digit in each line respectively.
-fn part2(input: &str) -> usize {
+        let values = entries.iter().map(|(_, value)| *value).collect();
 
-    // assign a value to each forward lexeme equal to lexeme_position % 10
-    let tokens: HashMap<_,_> = LEXEMES
-        .iter()
-        .enumerate()
-        .map(|(i, e)| (e, i % 10))
-        .collect();
+        Self { values, matcher: Matcher::new(&lexemes) }
+    }
 
-    let mut sum = 0;
+    /// The lexicon of part 1: plain digits only.
+    pub fn digits() -> Self {
+        Self::new(&DIGITS)
+    }
 
-    for line in input.lines() {
-        let first_lexeme = find(line.as_bytes(), &LEXEMES, Forward).unwrap();
-        let last_lexeme = find(line.as_bytes(), &LEXEMES, Backward).unwrap();
+    /// The lexicon of part 2: plain digits and English words.
+    pub fn english() -> Self {
+        Self::new(&[DIGITS, ENGLISH].concat())
+    }
+
+    fn token<'a>(&self, line: &'a str, m: Match) -> Token<'a> {
+        Token {
+            text: &line[m.start..m.end],
+            start: m.start,
+            value: self.values[m.pattern],
+        }
+    }
+
+    /// Returns all tokens in the line, ordered by where they end. Tokens
+    /// may overlap, so that "eightwo" yields both 8 and 2.
+    pub fn tokens<'a>(&self, line: &'a str) -> Vec<Token<'a>> {
+        self
+            .matcher
+            .matches(line.as_bytes())
+            .into_iter()
+            .map(|m| self.token(line, m))
+            .collect()
+    }
 
-        // retrieve the value associated with our lexemes
-        let first = tokens.get(&first_lexeme).unwrap();
-        let last = tokens.get(&last_lexeme).unwrap();
+    /// Returns the first and last token in the line, if any.
+    pub fn calibration<'a>(&self, line: &'a str) -> Option<Calibration<'a>> {
+        let first = self.matcher.first(line.as_bytes())?;
+        let last = self.matcher.last(line.as_bytes())?;
 
-        // [T]: You do the math.
-        // [J]: NO!
-        sum += first * 10 + last;
+        Some(Calibration {
+            first: self.token(line, first),
+            last: self.token(line, last),
+        })
     }
 
-    sum
+    /// Returns the sum of all calibration values in the document. Lines
+    /// without any token add nothing.
+    pub fn calibrate(&self, input: &str) -> usize {
+        input
+            .lines()
+            .filter_map(|line| self.calibration(line))
+            .map(|calibration| calibration.value())
+            .sum()
+    }
+}
+
+/// Returns the sum of all calibration values.
+fn part1(input: &str) -> usize {
+    Lexicon::digits().calibrate(input)
+}
+
+/// Returns the sum of all calibration values, where digits may be given as
+/// simple numbers or spelled out as letters.
+fn part2(input: &str) -> usize {
+    Lexicon::english().calibrate(input)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{part1, part2};
+    use super::{part1, part2, Lexicon, Token, DIGITS};
 
     const TEST_INPUT_1: &str = "1abc2
 pqr3stu8vwx
@@ -124,5 +145,40 @@ zoneight234
     fn test_part2() {
         assert_eq!(part2(TEST_INPUT_2), 281);
     }
+
+    #[test]
+    fn test_calibration() {
+        let lexicon = Lexicon::english();
+        let calibration = lexicon.calibration("zoneight234").unwrap();
+
+        assert_eq!(calibration.first, Token { text: "one", start: 1, value: 1 });
+        assert_eq!(calibration.last, Token { text: "4", start: 10, value: 4 });
+        assert_eq!(calibration.value(), 14);
+
+        let values: Vec<_> = lexicon
+            .tokens("eightwo1")
+            .iter()
+            .map(|token| token.value)
+            .collect();
+
+        assert_eq!(values, vec!(8, 2, 1));
+    }
+
+    #[test]
+    fn test_custom_lexicon() {
+        let roman = [("I", 1), ("II", 2), ("III", 3), ("IV", 4), ("V", 5)];
+        let lexicon = Lexicon::new(&[DIGITS.as_slice(), &roman].concat());
+
+        assert_eq!(lexicon.calibrate("xIVx7\nV"), 47 + 55);
+        assert_eq!(lexicon.calibrate("IIIxII\nVI"), 32 + 51);
+        assert!(lexicon.calibration("none").is_none());
+    }
+
+    #[test]
+    fn test_lines_without_digits() {
+        assert_eq!(part1("1abc2\nabc"), 12);
+        // "eightwothree" has no plain digits
+        assert_eq!(part1(TEST_INPUT_2), 11 + 22 + 33 + 42 + 24 + 77);
+    }
 }
 
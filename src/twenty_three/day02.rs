@@ -1,84 +1,228 @@
 use crate::Solution;
+use std::collections::HashMap;
 
 pub const SOLUTION: Solution<usize, usize> = Solution { part1, part2 };
 
-const MAX_RED: usize = 12;
-const MAX_GREEN: usize = 13;
-const MAX_BLUE: usize = 14;
+/// The bag the elf has in mind for part 1.
+pub const ELF_BAG: [(&str, usize); 3] = [
+    ("red", 12),
+    ("green", 13),
+    ("blue", 14),
+];
 
-struct Game {
-    id: usize,
-    min_red: usize,
-    min_green: usize,
-    min_blue: usize,
+/// A number of cubes per colour. Colours that are absent have no cubes.
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub struct Bag {
+    cubes: HashMap<String, usize>,
+}
+
+impl Bag {
+    pub fn new(cubes: &[(&str, usize)]) -> Self {
+        let cubes = cubes
+            .iter()
+            .map(|(colour, count)| (colour.to_string(), *count))
+            .collect();
+
+        Self { cubes }
+    }
+
+    pub fn count(&self, colour: &str) -> usize {
+        self.cubes.get(colour).copied().unwrap_or(0)
+    }
+
+    /// Returns whether this bag holds at least as many cubes of every colour
+    /// as the other bag.
+    pub fn contains(&self, other: &Bag) -> bool {
+        other
+            .cubes
+            .iter()
+            .all(|(colour, count)| self.count(colour) >= *count)
+    }
+
+    /// Grows this bag so that it also contains the other bag.
+    pub fn extend(&mut self, other: &Bag) {
+        for (colour, count) in &other.cubes {
+            let entry = self.cubes.entry(colour.clone()).or_insert(0);
+            *entry = (*entry).max(*count);
+        }
+    }
+
+    /// Adds the cubes of the other bag to this one.
+    pub fn add(&mut self, other: &Bag) {
+        for (colour, count) in &other.cubes {
+            *self.cubes.entry(colour.clone()).or_insert(0) += count;
+        }
+    }
+
+    pub fn colours(&self) -> impl Iterator<Item = &str> {
+        self.cubes.keys().map(|colour| colour.as_str())
+    }
+
+    /// Returns the product of the cube counts of the given colours, so
+    /// that a colour the bag lacks makes the power zero.
+    pub fn power(&self, colours: &[&str]) -> usize {
+        colours.iter().map(|colour| self.count(colour)).product()
+    }
+
+    /// Returns the product of the cube counts of the colours the bag
+    /// actually holds.
+    pub fn observed_power(&self) -> usize {
+        self.cubes.values().product()
+    }
+}
+
+/// Statistics on the number of cubes of a single colour drawn per round.
+/// Rounds in which the colour was not drawn count as zero cubes.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Summary {
+    pub min: usize,
+    pub max: usize,
+    pub total: usize,
+    pub mean: f64,
+}
+
+pub struct Game {
+    pub id: usize,
+    /// The cubes drawn from the bag in each round, in order.
+    pub rounds: Vec<Bag>,
 }
 
 impl Game {
-    fn from(description: &str) -> Self {
-        let (id_str, rounds_str) = description.split_once(':').unwrap();
-
-        let id = id_str[5..].parse::<usize>().unwrap();
-        let rounds = rounds_str.split(';');
-
-        let (mut min_red, mut min_green, mut min_blue) = (0, 0, 0);
-
-        for round in rounds {
-            let color_counts = round.split(',');
-
-            for color_count in color_counts {
-                let (count_str, color) = color_count[1..]
-                    .split_once(' ')
-                    .unwrap();
-                
-                let count = count_str.parse::<usize>().unwrap();
-
-                match color {
-                    "red" => min_red = min_red.max(count),
-                    "green" => min_green = min_green.max(count),
-                    "blue" => min_blue = min_blue.max(count),
-                    _ => panic!(),
-                };
-            }
+    /// Returns the fewest cubes of each colour that could have been in the
+    /// bag for this game to be played.
+    pub fn minimal_bag(&self) -> Bag {
+        let mut bag = Bag::default();
+
+        for round in &self.rounds {
+            bag.extend(round);
         }
 
-        Self { id, min_red, min_green, min_blue }
+        bag
     }
 
-    fn possible(
-        &self,
-        max_red: usize,
-        max_green: usize,
-        max_blue: usize
-    ) -> bool {
-        self.min_red <= max_red
-            && self.min_green <= max_green
-            && self.min_blue <= max_blue
+    pub fn possible(&self, bag: &Bag) -> bool {
+        self.first_impossible_round(bag).is_none()
     }
 
-    fn power(&self) -> usize {
-        self.min_red * self.min_green * self.min_blue
+    /// Returns the index of the first round that draws more cubes of some
+    /// colour than the bag holds.
+    pub fn first_impossible_round(&self, bag: &Bag) -> Option<usize> {
+        self.rounds.iter().position(|round| !bag.contains(round))
+    }
+
+    /// Returns the total number of cubes of each colour drawn over all
+    /// rounds.
+    pub fn totals(&self) -> Bag {
+        let mut totals = Bag::default();
+
+        for round in &self.rounds {
+            totals.add(round);
+        }
+
+        totals
+    }
+
+    /// Summarises the cubes of the given colour drawn per round, or returns
+    /// `None` if the game has no rounds.
+    pub fn summary(&self, colour: &str) -> Option<Summary> {
+        let counts = self.rounds.iter().map(|round| round.count(colour));
+
+        let total = counts.clone().sum();
+        let mean = total as f64 / self.rounds.len() as f64;
+
+        Some(Summary {
+            min: counts.clone().min()?,
+            max: counts.max()?,
+            total,
+            mean,
+        })
     }
 }
 
+impl TryFrom<&str> for Game {
+    type Error = ();
+
+    fn try_from(description: &str) -> Result<Self, Self::Error> {
+        let (id_str, rounds_str) = description.split_once(':').ok_or(())?;
+
+        let id = id_str
+            .strip_prefix("Game ")
+            .ok_or(())?
+            .parse::<usize>()
+            .or(Err(()))?;
+
+        let rounds = rounds_str
+            .split(';')
+            .map(|round| {
+                let mut draw = Bag::default();
+
+                for colour_count in round.split(',') {
+                    let (count_str, colour) = colour_count
+                        .trim()
+                        .split_once(' ')
+                        .ok_or(())?;
+
+                    let count = count_str.parse::<usize>().or(Err(()))?;
+
+                    draw.add(&Bag::new(&[(colour, count)]));
+                }
+
+                Ok(draw)
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { id, rounds })
+    }
+}
+
+/// Returns the games that could have been played with the given bag.
+pub fn possible_games<'a>(
+    games: &'a [Game],
+    bag: &'a Bag,
+) -> impl Iterator<Item = &'a Game> {
+    games.iter().filter(|game| game.possible(bag))
+}
+
+/// Returns the smallest bag with which all of the given games could have
+/// been played.
+pub fn minimal_bag<'a, I>(games: I) -> Bag
+    where I: IntoIterator<Item = &'a Game> {
+
+    let mut bag = Bag::default();
+
+    for game in games {
+        bag.extend(&game.minimal_bag());
+    }
+
+    bag
+}
+
+fn parse_games(input: &str) -> Result<Vec<Game>, ()> {
+    input.lines().map(Game::try_from).collect()
+}
+
 fn part1(input: &str) -> usize {
-    input
-        .lines()
-        .map(Game::from)
-        .filter(|game| game.possible(MAX_RED, MAX_GREEN, MAX_BLUE))
+    let games = parse_games(input).unwrap();
+
+    possible_games(&games, &Bag::new(&ELF_BAG))
         .map(|game| game.id)
         .sum()
 }
 
 fn part2(input: &str) -> usize {
-    input
-        .lines()
-        .map(|l| Game::from(l).power())
+    let colours: Vec<_> = ELF_BAG.iter().map(|(colour, _)| *colour).collect();
+
+    parse_games(input)
+        .unwrap()
+        .iter()
+        .map(|game| game.minimal_bag().power(&colours))
         .sum()
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{part1, part2};
+    use super::{minimal_bag, parse_games, part1, part2, possible_games, Bag};
+    use super::{Summary, ELF_BAG};
 
     const TEST_INPUT: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
 Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
@@ -95,5 +239,63 @@ Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
     fn test_part2() {
         assert_eq!(part2(TEST_INPUT), 2286);
     }
+
+    #[test]
+    fn test_queries() {
+        let games = parse_games(TEST_INPUT).unwrap();
+        let bag = Bag::new(&[("red", 6), ("green", 3), ("blue", 6)]);
+
+        let possible: Vec<_> = possible_games(&games, &bag)
+            .map(|game| game.id)
+            .collect();
+
+        assert_eq!(possible, vec!(1, 2, 5));
+        assert_eq!(
+            minimal_bag(&games[0..2]),
+            Bag::new(&[("red", 4), ("green", 3), ("blue", 6)])
+        );
+    }
+
+    #[test]
+    fn test_other_colours() {
+        let games = parse_games("Game 7: 2 cyan, 1 red; 3 magenta").unwrap();
+
+        let bag = Bag::new(&[("cyan", 2), ("magenta", 3), ("red", 1)]);
+        assert!(games[0].possible(&bag));
+
+        let bag = Bag::new(&[("cyan", 2), ("red", 1)]);
+        assert!(!games[0].possible(&bag));
+
+        assert_eq!(games[0].minimal_bag().observed_power(), 6);
+        assert_eq!(games[0].minimal_bag().power(&["cyan", "magenta"]), 6);
+    }
+
+    #[test]
+    fn test_missing_colour() {
+        // no blue cubes are ever drawn, so the power is zero
+        assert_eq!(part2("Game 1: 3 red, 2 green; 1 red"), 0);
+    }
+
+    #[test]
+    fn test_rounds() {
+        let games = parse_games(TEST_INPUT).unwrap();
+        let bag = Bag::new(&ELF_BAG);
+
+        assert_eq!(games[2].rounds.len(), 3);
+        assert_eq!(games[2].rounds[1].count("green"), 13);
+        assert_eq!(games[2].first_impossible_round(&bag), Some(0));
+        assert_eq!(games[3].first_impossible_round(&bag), Some(2));
+        assert_eq!(games[0].first_impossible_round(&bag), None);
+
+        assert_eq!(
+            games[0].totals(),
+            Bag::new(&[("red", 5), ("green", 4), ("blue", 9)])
+        );
+
+        assert_eq!(
+            games[0].summary("green"),
+            Some(Summary { min: 0, max: 2, total: 4, mean: 4.0 / 3.0 })
+        );
+    }
 }
 
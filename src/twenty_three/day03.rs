@@ -3,112 +3,172 @@ use regex::Regex;
 
 pub const SOLUTION: Solution<usize, usize> = Solution { part1, part2 };
 
-struct Pos {
-    x: i64,
-    y: i64,
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Pos {
+    pub x: i64,
+    pub y: i64,
 }
 
 impl Pos {
-    fn from_offset(offset: usize, line_len: usize) -> Self {
-        Self {
-            x: (offset % line_len) as i64,
-            y: (offset.div_euclid(line_len)) as i64
-        }
+    fn neighbours(&self) -> impl Iterator<Item = Pos> + '_ {
+        (-1..=1)
+            .flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+            .filter(|delta| *delta != (0, 0))
+            .map(|(dx, dy)| Pos { x: self.x + dx, y: self.y + dy })
     }
 }
 
 struct Part<'a> {
     num_str: &'a str,
-    pos: Pos
 }
 
 impl Part<'_> {
     fn num(&self) -> usize {
         self.num_str.parse::<usize>().unwrap()
     }
+}
+
+/// An engine schematic, indexed so that the parts adjacent to any cell can
+/// be found by looking at its eight neighbours rather than at every part.
+pub struct Schematic<'a> {
+    width: usize,
+    height: usize,
+    parts: Vec<Part<'a>>,
+    /// The id of the part covering each cell, if any, in row-major order.
+    cells: Vec<Option<usize>>,
+    symbols: Vec<(Pos, char)>,
+}
+
+impl Schematic<'_> {
+    fn part_at(&self, pos: &Pos) -> Option<usize> {
+        if pos.x < 0
+            || pos.x >= self.width as i64
+            || pos.y < 0
+            || pos.y >= self.height as i64 {
+            return None;
+        }
+
+        self.cells[pos.y as usize * self.width + pos.x as usize]
+    }
+
+    /// Returns the ids of all distinct parts adjacent to the position.
+    fn adjacent_parts(&self, pos: &Pos) -> Vec<usize> {
+        let mut ids: Vec<_> = pos
+            .neighbours()
+            .filter_map(|neighbour| self.part_at(&neighbour))
+            .collect();
+
+        ids.sort();
+        ids.dedup();
+
+        ids
+    }
+
+    /// Returns every symbol matching `symbol` together with the numbers of
+    /// its adjacent parts, keeping only those symbols for which the number
+    /// of adjacent parts satisfies `arity`.
+    pub fn query<S, A>(&self, symbol: S, arity: A) -> Vec<SymbolParts>
+        where S: Fn(char) -> bool, A: Fn(usize) -> bool {
+
+        self
+            .symbols
+            .iter()
+            .filter(|(_, c)| symbol(*c))
+            .map(|(pos, c)| SymbolParts {
+                pos: *pos,
+                symbol: *c,
+                parts: self
+                    .adjacent_parts(pos)
+                    .iter()
+                    .map(|id| self.parts[*id].num())
+                    .collect(),
+            })
+            .filter(|symbol_parts| arity(symbol_parts.parts.len()))
+            .collect()
+    }
+
+    /// Returns the number of every part in the schematic together with the
+    /// symbols it touches, in reading order.
+    pub fn part_symbols(&self) -> Vec<(usize, Vec<(Pos, char)>)> {
+        let mut touching = vec![vec!(); self.parts.len()];
 
-    fn adjacent(&self, pos: &Pos) -> bool {
-        pos.x >= self.pos.x - 1
-            && pos.x <= self.pos.x + self.num_str.len() as i64
-            && pos.y >= self.pos.y - 1
-            && pos.y <= self.pos.y + 1
+        for (pos, c) in &self.symbols {
+            for id in self.adjacent_parts(pos) {
+                touching[id].push((*pos, *c));
+            }
+        }
+
+        self
+            .parts
+            .iter()
+            .map(|part| part.num())
+            .zip(touching)
+            .collect()
     }
 }
 
-fn part1(input: &str) -> usize {
-    let line_len = input.find('\n').unwrap();
-    let stripped = input.replace('\n', "");
-
-    let symbols: Vec<_> = stripped
-        .char_indices()
-        .filter(|(_, c)| *c != '.' && !c.is_ascii_alphanumeric()) //find symbols
-        .map(|(i, _)| Pos::from_offset(i, line_len))
-        .collect();
-
-    let re = Regex::new(r"[0-9]+").unwrap(); // find possible parts
-
-    re
-        .find_iter(&stripped)
-        .map(|m| {
-            Part { 
-                num_str: m.as_str(),
-                pos: Pos::from_offset(m.start(), line_len)
+/// A symbol in the schematic along with the numbers of its adjacent parts.
+#[derive(PartialEq, Eq, Debug)]
+pub struct SymbolParts {
+    pub pos: Pos,
+    pub symbol: char,
+    pub parts: Vec<usize>,
+}
+
+impl<'a> From<&'a str> for Schematic<'a> {
+    fn from(input: &'a str) -> Self {
+        let re = Regex::new(r"[0-9]+").unwrap(); // find possible parts
+
+        let width = input.lines().map(|l| l.len()).max().unwrap_or(0);
+        let height = input.lines().count();
+
+        let mut parts = vec!();
+        let mut cells = vec![None; width * height];
+        let mut symbols = vec!();
+
+        for (y, line) in input.lines().enumerate() {
+            for m in re.find_iter(line) {
+                for x in m.range() {
+                    cells[y * width + x] = Some(parts.len());
+                }
+
+                parts.push(Part { num_str: m.as_str() });
             }
-        })
-        .filter(|part| {
-            // select parts that are adjacent to a symbol
-           symbols 
-                .iter()
-                .any(|sym| part.adjacent(sym))
-        })
-        .map(|part| part.num())
+
+            let line_symbols = line
+                .char_indices()
+                .filter(|(_, c)| *c != '.' && !c.is_ascii_alphanumeric())
+                .map(|(x, c)| (Pos { x: x as i64, y: y as i64 }, c));
+
+            symbols.extend(line_symbols);
+        }
+
+        Self { width, height, parts, cells, symbols }
+    }
+}
+
+fn part1(input: &str) -> usize {
+    // select parts that are adjacent to a symbol
+    Schematic::from(input)
+        .part_symbols()
+        .iter()
+        .filter(|(_, symbols)| !symbols.is_empty())
+        .map(|(num, _)| num)
         .sum()
 }
 
 fn part2(input: &str) -> usize {
-    let line_len = input.find('\n').unwrap();
-    let stripped = input.replace('\n', "");
-
-    let gears: Vec<_> = stripped
-        .match_indices('*')  // find gears
-        .map(|(i, _)| Pos::from_offset(i, line_len))
-        .collect();
-
-    let re = Regex::new(r"[0-9]+").unwrap();
-
-    let parts: Vec<_> = re
-        .find_iter(&stripped) // find possible parts
-        .map(|m| {
-            Part { 
-                num_str: m.as_str(),
-                pos: Pos::from_offset(m.start(), line_len)
-            }
-        })
-        .collect();
-
-    gears
+    // find gears with exactly two parts attached
+    Schematic::from(input)
+        .query(|c| c == '*', |arity| arity == 2)
         .iter()
-        .map(|gear| {
-            // find any parts attached to the gear
-            let adjacent_parts: Vec<_> = parts
-                .iter()
-                .filter(|part| part.adjacent(gear))
-                .collect();
-
-            // verify that there are exactly two parts attached to the gear
-            if adjacent_parts.len() == 2 {
-                adjacent_parts.iter().map(|part| part.num()).product()
-            }
-            else {
-                0
-            }
-        })
+        .map(|gear| gear.parts.iter().product::<usize>())
         .sum()
-    }
+}
 
 #[cfg(test)]
 mod tests {
-    use super::{part1, part2};
+    use super::{part1, part2, Pos, Schematic, SymbolParts};
 
     const TEST_INPUT: &str = "467..114..
 ...*......
@@ -130,5 +190,48 @@ mod tests {
     fn test_part2() {
         assert_eq!(part2(TEST_INPUT), 467835);
     }
+
+    #[test]
+    fn test_query() {
+        let schematic = Schematic::from(TEST_INPUT);
+
+        let hash = SymbolParts {
+            pos: Pos { x: 6, y: 3 },
+            symbol: '#',
+            parts: vec!(633),
+        };
+
+        let dollar = SymbolParts {
+            pos: Pos { x: 3, y: 8 },
+            symbol: '$',
+            parts: vec!(664),
+        };
+
+        assert_eq!(
+            schematic.query(|c| "#$".contains(c), |arity| arity >= 1),
+            vec!(hash, dollar)
+        );
+
+        assert_eq!(schematic.query(|_| true, |arity| arity > 2), vec!());
+    }
+
+    #[test]
+    fn test_part_symbols() {
+        let schematic = Schematic::from(TEST_INPUT);
+
+        let shared: Vec<_> = schematic
+            .part_symbols()
+            .into_iter()
+            .filter(|(_, symbols)| symbols.len() > 1)
+            .collect();
+
+        assert!(shared.is_empty());
+
+        let schematic = Schematic::from("12*\n#..");
+
+        let symbols = vec!((Pos { x: 2, y: 0 }, '*'), (Pos { x: 0, y: 1 }, '#'));
+
+        assert_eq!(schematic.part_symbols(), vec!((12, symbols)));
+    }
 }
 
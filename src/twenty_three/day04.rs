@@ -1,13 +1,35 @@
 use crate::Solution;
+use std::collections::HashSet;
 
 pub const SOLUTION: Solution<usize, usize> = Solution { part1, part2 };
 
-struct Card {
-    winning: Vec<usize>,
+pub struct Card {
+    winning: HashSet<usize>,
     owned: Vec<usize>,
 }
 
 impl Card {
+    pub fn matches(&self) -> usize {
+        self
+            .owned
+            .iter()
+            .filter(|n| self.winning.contains(n))
+            .count()
+    }
+
+    pub fn points(&self) -> usize {
+        let matches = self.matches();
+
+        if matches > 0 {
+            2usize.pow(matches as u32 - 1)
+        }
+        else {
+            0
+        }
+    }
+}
+
+impl From<&str> for Card {
     fn from(description: &str) -> Self {
         let (winning_str, owned_str) = description
             .split_once(':')
@@ -26,60 +48,56 @@ impl Card {
 
         Self { winning, owned }
     }
+}
 
-    fn matches(&self) -> usize {
-        self
-            .owned
-            .iter()
-            .filter(|n| self.winning.contains(n))
-            .count()
-    }
+pub struct Scratchcards {
+    pub cards: Vec<Card>,
+}
 
-    fn points(&self) -> usize {
-        let matches = self.matches();
+impl Scratchcards {
+    /// Returns how many copies of each card end up being held, counting the
+    /// original. A card with n matches wins one copy of each of the next n
+    /// cards. Copies won past the end of the table are discarded, since
+    /// there are no such cards to copy.
+    pub fn copies(&self) -> Vec<usize> {
+        let mut card_count = vec![1; self.cards.len()];
 
-        if matches > 0 {
-            2usize.pow(matches as u32 - 1)
-        }
-        else {
-            0
+        for (i, card) in self.cards.iter().enumerate() {
+            let won = (i + 1)..(i + 1 + card.matches()).min(self.cards.len());
+
+            for j in won {
+                card_count[j] += card_count[i];
+            }
         }
+
+        card_count
+    }
+}
+
+impl From<&str> for Scratchcards {
+    fn from(input: &str) -> Self {
+        Self { cards: input.lines().map(Card::from).collect() }
     }
 }
 
 fn part1(input: &str) -> usize {
-    input
-        .lines()
-        .map(Card::from)
+    Scratchcards::from(input)
+        .cards
+        .iter()
         .map(|card| card.points())
         .sum()
 }
 
 fn part2(input: &str) -> usize {
-    let cards: Vec<_> = input
-        .lines()
-        .map(Card::from)
-        .collect();
-
-    let mut card_count: Vec<usize> = cards
+    Scratchcards::from(input)
+        .copies()
         .iter()
-        .map(|_| 1)
-        .collect();
-
-    for (i, card) in cards.iter().enumerate() {
-        let matches = card.matches();
-
-        for m in 0..matches {
-            card_count[i + 1 + m] += card_count[i];
-        }
-    }
-
-    card_count.iter().sum()
+        .sum()
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{part1, part2};
+    use super::{part1, part2, Scratchcards};
 
     const TEST_INPUT: &str = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
 Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
@@ -97,5 +115,18 @@ Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
     fn test_part2() {
         assert_eq!(part2(TEST_INPUT), 30);
     }
+
+    #[test]
+    fn test_copies() {
+        let scratchcards = Scratchcards::from(TEST_INPUT);
+
+        assert_eq!(scratchcards.copies(), vec!(1, 2, 4, 8, 14, 1));
+
+        // the last card's matches would win copies past the end of the table
+        let scratchcards = Scratchcards::from("Card 1: 1 | 2
+Card 2: 3 4 | 3 4");
+
+        assert_eq!(scratchcards.copies(), vec!(1, 1));
+    }
 }
 
@@ -1,42 +1,110 @@
 use crate::Solution;
+use num::Integer;
+use num::integer::Roots;
 
 pub const SOLUTION: Solution<usize, usize> = Solution { part1, part2 };
 
-fn solve_quadratic(a: f64, b: f64, c: f64) -> Option<(f64, f64)> {
-    let discriminant = b.powi(2) - 4.0 * a * c;
+/// A race of `time` milliseconds in which the boat has to travel further
+/// than `record` millimetres. Each millisecond spent holding the button
+/// increases the boat's speed by `acceleration` millimetres per millisecond.
+/// Generic over the integer type. Solving a race squares its time, so the
+/// default of `u128` handles any time that fits in a `u64`, and big
+/// integers handle anything longer.
+pub struct Race<T = u128> {
+    pub time: T,
+    pub record: T,
+    pub acceleration: T,
+}
 
-    if discriminant < 0.0 {
-        return None;
+impl<T: Integer + Roots + Clone> Race<T> {
+    /// Creates a race with the standard acceleration of 1 mm/ms per ms.
+    pub fn new(time: T, record: T) -> Self {
+        Self { time, record, acceleration: T::one() }
     }
 
-    let x = (
-        (-b + discriminant.sqrt()) / 2.0 * a,
-        (-b - discriminant.sqrt()) / 2.0 * a,
-    );
+    /// Returns the distance travelled when holding the button for `hold`
+    /// milliseconds. Holding the button for the entire race or longer
+    /// leaves no time to move at all.
+    pub fn distance(&self, hold: &T) -> T {
+        if *hold >= self.time {
+            return T::zero();
+        }
 
-    Some(x)
-}
+        self.acceleration.clone()
+            * hold.clone()
+            * (self.time.clone() - hold.clone())
+    }
 
-struct Race {
-    time: isize,
-    record: isize,
-}
+    fn wins(&self, hold: &T) -> bool {
+        self.distance(hold) > self.record
+    }
+
+    /// Returns how far the boat beats the record by when holding the button
+    /// for `hold` milliseconds, or `None` if it does not beat the record.
+    pub fn margin(&self, hold: &T) -> Option<T> {
+        if !self.wins(hold) {
+            return None;
+        }
 
-impl Race {
-    fn winning_times(&self) -> (usize, usize) {
-        let times = solve_quadratic(
-            -1.0,
-            self.time as f64,
-            -self.record as f64
-        ).unwrap();
+        Some(self.distance(hold) - self.record.clone())
+    }
 
-        (times.0.floor() as usize + 1, times.1.ceil() as usize)
+    /// Returns the hold time that travels the furthest. The distance is
+    /// symmetric around time / 2, so for odd times, holding for one
+    /// millisecond longer is just as good.
+    pub fn optimal_hold(&self) -> T {
+        self.time.clone() / (T::one() + T::one())
     }
 
-    fn winning_ways(&self) -> usize {
-        let winning_interval = self.winning_times();
+    /// Returns the shortest and longest winning hold times, if any. The
+    /// winning hold times h satisfy a * h * (time - h) > record, which is
+    /// an interval around time / 2. Since all values are integers, this is
+    /// equivalent to h * (time - h) > record / a, rounded down. The bounds
+    /// are estimated with an integer square root of the discriminant and
+    /// then corrected for rounding, so the result is exact for any integer
+    /// size.
+    pub fn winning_times(&self) -> Option<(T, T)> {
+        let two = T::one() + T::one();
+        let four = two.clone() * two.clone();
+
+        // the distance peaks at time / 2, so there is no winning hold time
+        // unless holding for that long beats the record
+        if !self.wins(&self.optimal_hold()) {
+            return None;
+        }
+
+        let record = self.record.clone() / self.acceleration.clone();
+        let discriminant = self.time.clone() * self.time.clone()
+            - four * record;
+        let root = discriminant.sqrt();
+
+        let mut shortest = (self.time.clone() - root) / two;
+
+        while !self.wins(&shortest) {
+            shortest = shortest + T::one();
+        }
+
+        while shortest > T::zero() {
+            let shorter = shortest.clone() - T::one();
 
-        winning_interval.1 - winning_interval.0
+            if !self.wins(&shorter) {
+                break;
+            }
+
+            shortest = shorter;
+        }
+
+        // distances are symmetric around time / 2
+        let longest = self.time.clone() - shortest.clone();
+
+        Some((shortest, longest))
+    }
+
+    pub fn winning_ways(&self) -> T {
+        match self.winning_times() {
+            Some((shortest, longest)) => longest - shortest + T::one(),
+            None => T::zero(),
+        }
     }
 }
 
@@ -46,7 +114,7 @@ fn part1(input: &str) -> usize {
         .map(|l| l
              .split_whitespace()
              .skip(1)
-             .map(|n| n.parse::<isize>().unwrap())
+             .map(|n| n.parse::<u128>().unwrap())
              .collect::<Vec<_>>()
         )
         .collect::<Vec<_>>();
@@ -54,31 +122,32 @@ fn part1(input: &str) -> usize {
     pairs[0]
         .iter()
         .zip(&pairs[1])
-        .map(|r| Race { time: *r.0, record: *r.1 }.winning_ways())
+        .map(|r| Race::new(*r.0, *r.1).winning_ways() as usize)
         .product()
 }
 
 fn part2(input: &str) -> usize {
-    let race_data: Vec<isize> = input
+    let race_data: Vec<u128> = input
         .replace(' ', "")
         .lines()
         .map(|l| l
              .split_once(':')
              .unwrap()
              .1
-             .parse::<isize>()
+             .parse::<u128>()
              .unwrap()
         )
         .collect();
 
-    let race = Race { time: race_data[0], record: race_data[1] };
+    let race = Race::new(race_data[0], race_data[1]);
 
-    race.winning_ways()
+    race.winning_ways() as usize
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{part1, part2};
+    use super::{part1, part2, Race};
+    use num::BigUint;
 
     const TEST_INPUT: &str = "Time:      7  15   30
 Distance:  9  40  200";
@@ -92,5 +161,105 @@ Distance:  9  40  200";
     fn test_part2() {
         assert_eq!(part2(TEST_INPUT), 71503);
     }
+
+    fn brute_force(time: u64, record: u64) -> u64 {
+        brute_force_accelerated(time, record, 1)
+    }
+
+    fn brute_force_accelerated(
+        time: u64,
+        record: u64,
+        acceleration: u64,
+    ) -> u64 {
+        (0..=time)
+            .filter(|hold| acceleration * hold * (time - hold) > record)
+            .count() as u64
+    }
+
+    #[test]
+    fn test_against_brute_force() {
+        for time in 0..=60 {
+            for record in 0..=(time * time / 4 + 2) {
+                let race = Race::new(time, record);
+
+                assert_eq!(
+                    race.winning_ways(),
+                    brute_force(time, record),
+                    "time {}, record {}",
+                    time,
+                    record
+                );
+
+                let (time, record) = (time as u128, record as u128);
+                let race = Race::new(time, record);
+
+                assert_eq!(
+                    race.winning_ways(),
+                    brute_force(time as u64, record as u64).into()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_strategy() {
+        let race = Race::new(7, 9);
+
+        assert_eq!(race.winning_times(), Some((2, 5)));
+        assert_eq!(race.optimal_hold(), 3);
+        assert_eq!(race.distance(&3), 12);
+        assert_eq!(race.distance(&8), 0);
+        assert_eq!(race.margin(&3), Some(3));
+        assert_eq!(race.margin(&1), None);
+
+        // tying the record is not a win
+        assert_eq!(Race::new(7, 12).margin(&3), None);
+    }
+
+    #[test]
+    fn test_acceleration() {
+        for acceleration in 0..=4 {
+            for time in 0..=30 {
+                for record in 0..=(acceleration * time * time / 4 + 2) {
+                    let race = Race { time, record, acceleration };
+
+                    assert_eq!(
+                        race.winning_ways(),
+                        brute_force_accelerated(time, record, acceleration)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_large_races() {
+        // the record is one short of the best possible distance, which
+        // floating point cannot tell apart at this size
+        let time = 1u128 << 60;
+        let race = Race::new(time, time * time / 4 - 1);
+
+        assert_eq!(race.winning_times(), Some((time / 2, time / 2)));
+
+        let time = BigUint::from(10u8).pow(40);
+        let record = &time * &time / 4u8 - 1u8;
+        let race = Race::new(time, record);
+
+        assert_eq!(race.winning_ways(), BigUint::from(1u8));
+    }
+
+    #[test]
+    fn test_u64_boundary() {
+        // times past 2^32 already overflow when squared in a u64
+        let race: Race = Race::new(5_000_000_000, 1);
+        assert_eq!(race.winning_ways(), 4_999_999_999);
+
+        let time = u64::MAX as u128;
+        let race: Race = Race::new(time, 0);
+        assert_eq!(race.winning_times(), Some((1, time - 1)));
+
+        let input = format!("Time: {}\nDistance: 0", u64::MAX);
+        assert_eq!(part2(&input), (u64::MAX - 1) as usize);
+    }
 }
 
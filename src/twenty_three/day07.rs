@@ -3,164 +3,314 @@ use std::cmp::Ordering;
 
 pub const SOLUTION: Solution<usize, usize> = Solution { part1, part2 };
 
-#[derive(PartialEq, Eq, PartialOrd, Ord)]
-enum HandType {
-    HighCard,
-    OnePair,
-    TwoPair,
-    ThreeOfAKind,
-    FullHouse,
-    FourOfAKind,
-    FiveOfAKind,
+/// The type of a hand, given by how many times each distinct card occurs in
+/// it, from most to least often. A full house of five cards is [3, 2] and two
+/// pair is [2, 2, 1], but hands of any size have a type. Types compare by
+/// their first differing count, which ranks the usual five card types in
+/// their usual order.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HandType {
+    signature: Vec<u8>,
 }
 
-#[derive(Clone)]
-struct Hand {
-    cards: [u8; 5],
-    bid: usize
-}
+impl HandType {
+    /// Creates the hand type with the given card counts, in any order.
+    pub fn new(counts: &[u8]) -> Self {
+        let mut signature: Vec<_> = counts
+            .iter()
+            .copied()
+            .filter(|count| *count > 0)
+            .collect();
 
-impl Hand {
-    fn hand_type(&self) -> HandType {
-        let mut counts = [0u8; 15];
+        signature.sort();
+        signature.reverse();
 
-        for card in self.cards {
-            counts[card as usize] += 1;
-        }
+        Self { signature }
+    }
+
+    pub fn signature(&self) -> &[u8] {
+        &self.signature
+    }
 
-        let jokers = counts[1];
-        let remaining_counts = &mut counts[2..];
+    /// The size of the hand.
+    pub fn cards(&self) -> usize {
+        self.signature.iter().map(|count| *count as usize).sum()
+    }
 
-        remaining_counts.sort();
+    /// All types of hands of the given size, from weakest to strongest.
+    pub fn all(size: usize) -> Vec<HandType> {
+        let mut types = vec!();
+        Self::partitions(size as u8, size as u8, &mut vec!(), &mut types);
 
-        match counts[counts.len() - 1] + jokers {
-            5 => HandType::FiveOfAKind,
-            4 => HandType::FourOfAKind,
-            3 => match counts[counts.len() - 2] {
-                2 => HandType::FullHouse,
-                _ => HandType::ThreeOfAKind,
-            },
-            2 => match counts[counts.len() - 2] {
-                2 => HandType::TwoPair,
-                _ => HandType::OnePair
-            },
-            _ => HandType::HighCard,
+        types.reverse();
+        types
+    }
+
+    /// Adds every way to split `remaining` cards into counts of at most
+    /// `max` to `types`, strongest first.
+    fn partitions(
+        remaining: u8,
+        max: u8,
+        counts: &mut Vec<u8>,
+        types: &mut Vec<HandType>,
+    ) {
+        if remaining == 0 {
+            types.push(Self { signature: counts.clone() });
+            return;
+        }
+
+        for count in (1..=max.min(remaining)).rev() {
+            counts.push(count);
+            Self::partitions(remaining - count, count, counts, types);
+            counts.pop();
         }
     }
 }
 
+/// The rules of a game of Camel Cards: how cards are ordered, which cards
+/// are wild and how hand types are ranked.
+pub struct RuleSet {
+    /// Card labels from weakest to strongest.
+    pub order: Vec<char>,
+    /// Cards that act like whichever card makes the hand strongest. They
+    /// are still ranked by `order` when breaking ties.
+    pub wildcards: Vec<char>,
+    /// Hand types from weakest to strongest, replacing the ranking by
+    /// signature. Types that are not listed rank below all listed ones.
+    pub type_order: Option<Vec<HandType>>,
+}
 
-impl PartialEq for Hand {
-    fn eq(&self, other: &Self) -> bool {
-        self.cards.eq(&other.cards)
+impl RuleSet {
+    pub fn new(order: &str, wildcards: &str) -> Self {
+        Self {
+            order: order.chars().collect(),
+            wildcards: wildcards.chars().collect(),
+            type_order: None,
+        }
     }
-}
 
-impl Eq for Hand {}
+    /// The rules of part 1.
+    pub fn standard() -> Self {
+        Self::new("23456789TJQKA", "")
+    }
 
-impl PartialOrd for Hand {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+    /// The rules of part 2, where jacks become jokers: wild, but the
+    /// weakest card when breaking ties.
+    pub fn jokers() -> Self {
+        Self::new("J23456789TQKA", "J")
+    }
+
+    fn rank(&self, card: char) -> Option<u8> {
+        self.order.iter().position(|c| *c == card).map(|r| r as u8)
     }
-}
 
-impl Ord for Hand {
-    fn cmp(&self, other: &Self) -> Ordering {
-        match self.hand_type().cmp(&other.hand_type()) {
-            Ordering::Equal => {
-                let pairs = self
-                    .cards
+    fn compare_types(&self, l: &HandType, r: &HandType) -> Ordering {
+        let Some(type_order) = &self.type_order else {
+            return l.cmp(r);
+        };
+
+        let rank = |hand_type| type_order
+            .iter()
+            .position(|t| t == hand_type)
+            .map_or(0, |r| r + 1);
+
+        rank(l).cmp(&rank(r))
+    }
+
+    fn hand_type(&self, cards: &[char]) -> HandType {
+        let mut counts = vec![0u8; self.order.len()];
+        let mut jokers = 0;
+
+        for card in cards {
+            if self.wildcards.contains(card) {
+                jokers += 1;
+            }
+            else if let Some(rank) = self.rank(*card) {
+                counts[rank as usize] += 1;
+            }
+        }
+
+        // wildcards are best spent on the most common card
+        let mut hand_type = HandType::new(&counts);
+
+        match hand_type.signature.first_mut() {
+            Some(most) => *most += jokers,
+            None if jokers > 0 => hand_type.signature.push(jokers),
+            None => {},
+        }
+
+        hand_type
+    }
+
+    /// Compares two hands dealt under these rules, first by type and then
+    /// card by card.
+    pub fn compare(&self, l: &Hand, r: &Hand) -> Ordering {
+        self
+            .compare_types(&l.hand_type, &r.hand_type)
+            .then_with(|| l.cards.cmp(&r.cards))
+    }
+
+    /// Packs the type and cards of a hand into a single integer that orders
+    /// hands of at most `size` cards the same way as `compare`, or returns
+    /// `None` if they do not fit. Counts and ranks are stored in as few
+    /// bits as they need, so five card hands take 5 * 3 + 5 * 4 bits.
+    fn sort_key(&self, hand: &Hand, size: usize) -> Option<u128> {
+        let bits = |max: usize| usize::BITS - max.leading_zeros();
+
+        let (type_bits, type_values) = match &self.type_order {
+            Some(type_order) => {
+                let rank = type_order
                     .iter()
-                    .zip(other.cards.iter());
+                    .position(|t| *t == hand.hand_type)
+                    .map_or(0, |r| r + 1);
+
+                (bits(type_order.len()), vec!(rank))
+            },
+            None => {
+                let mut counts = vec![0; size];
 
-                for (l, r) in pairs {
-                    if l != r {
-                        return l.cmp(r);
-                    }
+                for (i, count) in hand.hand_type.signature.iter().enumerate() {
+                    counts[i] = *count as usize;
                 }
 
-                Ordering::Equal
+                (bits(size), counts)
             },
-            ordering => ordering,
+        };
+
+        // shorter hands sort before longer hands with the same prefix, so
+        // missing cards get a value below every rank
+        let card_bits = bits(self.order.len());
+        let card_values = (0..size)
+            .map(|i| hand.cards.get(i).map_or(0, |rank| *rank as usize + 1));
+
+        let total = type_bits as usize * type_values.len()
+            + card_bits as usize * size;
+
+        if total > u128::BITS as usize {
+            return None;
         }
+
+        let mut key = 0u128;
+
+        for value in type_values {
+            key = key << type_bits | value as u128;
+        }
+
+        for value in card_values {
+            key = key << card_bits | value as u128;
+        }
+
+        Some(key)
     }
 }
 
-impl TryFrom<&str> for Hand {
-    type Error = ();
+#[derive(Clone)]
+pub struct Hand {
+    /// The rank of each card under the rule set the hand was dealt with.
+    cards: Vec<u8>,
+    pub hand_type: HandType,
+    pub bid: usize,
+    /// Orders the hand among the other hands of its game, if they are small
+    /// enough to be packed into an integer.
+    key: Option<u128>,
+}
 
-    fn try_from(input: &str) -> Result<Self, Self::Error> {
-        let (cards_str, bid_str) = input.split_once(' ').ok_or(())?;
+impl Hand {
+    fn parse(input: &str, rules: &RuleSet) -> Option<Self> {
+        let (cards_str, bid_str) = input.split_once(' ')?;
+
+        let labels: Vec<_> = cards_str.chars().collect();
 
-        let cards = cards_str
-            .as_bytes()
+        let cards = labels
             .iter()
-            .map(|b| match b {
-                b'1'..=b'9' => *b - b'0',
-                b'T' => 10,
-                b'J' => 11,
-                b'Q' => 12,
-                b'K' => 13,
-                b'A' => 14,
-                _ => panic!(),
-            })
-            .collect::<Vec<_>>()
-            .try_into()
-            .or(Err(()))?;
-
-        let bid = bid_str.parse().or(Err(()))?;
-
-        Ok(Self { cards, bid })
+            .map(|c| rules.rank(*c))
+            .collect::<Option<_>>()?;
+
+        let hand_type = rules.hand_type(&labels);
+        let bid = bid_str.parse().ok()?;
+
+        Some(Self { cards, hand_type, bid, key: None })
     }
 }
 
-struct Game {
+pub struct Game {
+    rules: RuleSet,
     hands: Vec<Hand>,
 }
 
 impl Game {
-    fn winnings(&self) -> usize {
-        let mut ranking = self.hands.to_vec();
-        ranking.sort();
+    /// Parses a list of hands and bids, or returns `None` if any hand holds
+    /// a card the rules do not know about. Hands may hold any number of
+    /// cards, but are meant to all be the same size.
+    pub fn parse(input: &str, rules: RuleSet) -> Option<Self> {
+        let mut hands = input
+            .lines()
+            .map(|line| Hand::parse(line, &rules))
+            .collect::<Option<Vec<_>>>()?;
 
-        ranking
+        // classify every hand once, rather than on every comparison
+        let size = hands
             .iter()
-            .enumerate()
-            .map(|(i, hand)| hand.bid * (i + 1))
-            .sum()
+            .map(|hand| hand.cards.len())
+            .max()
+            .unwrap_or(0);
+
+        for hand in &mut hands {
+            hand.key = rules.sort_key(hand, size);
+        }
+
+        Some(Self { rules, hands })
     }
-}
 
-impl TryFrom<&str> for Game {
-    type Error = ();
+    pub fn hands(&self) -> &[Hand] {
+        &self.hands
+    }
 
-    fn try_from(input: &str) -> Result<Self, Self::Error> {
-        let hands = input
-            .lines()
-            .map(|line| Hand::try_from(line).unwrap())
-            .collect();
+    pub fn winnings(&self) -> usize {
+        // keys are either present for all hands or for none
+        let keyed = self
+            .hands
+            .iter()
+            .map(|hand| Some((hand.key?, hand.bid)))
+            .collect::<Option<Vec<_>>>();
+
+        let bids: Vec<_> = match keyed {
+            Some(mut keyed) => {
+                // a stable sort keeps equal hands in input order, exactly
+                // like sorting with `compare`
+                keyed.sort_by_key(|(key, _)| *key);
+                keyed.iter().map(|(_, bid)| *bid).collect()
+            },
+            None => {
+                let mut ranking: Vec<_> = self.hands.iter().collect();
+                ranking.sort_by(|l, r| self.rules.compare(l, r));
+                ranking.iter().map(|hand| hand.bid).collect()
+            },
+        };
 
-        Ok(Self { hands })
+        bids
+            .iter()
+            .enumerate()
+            .map(|(i, bid)| bid * (i + 1))
+            .sum()
     }
 }
 
 fn part1(input: &str) -> usize {
-    Game::try_from(input)
+    Game::parse(input, RuleSet::standard())
         .unwrap()
         .winnings()
 }
 
 fn part2(input: &str) -> usize {
-    let input = input.replace('J', "1");
-
-    Game::try_from(input.as_str())
+    Game::parse(input, RuleSet::jokers())
         .unwrap()
         .winnings()
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{part1, part2};
+    use super::{part1, part2, Game, HandType, RuleSet};
 
     const TEST_INPUT: &str = "32T3K 765
 T55J5 684
@@ -177,5 +327,98 @@ QQQJA 483";
     fn test_part2() {
         assert_eq!(part2(TEST_INPUT), 5905);
     }
-}
 
+    #[test]
+    fn test_variants() {
+        // both jacks and twos are wild
+        let rules = RuleSet::new("2J3456789TQKA", "2J");
+        let hand_type = rules.hand_type(&['2', 'J', 'A', 'K', 'A']);
+        assert_eq!(hand_type, HandType::new(&[4, 1]));
+
+        // aces low: the hand with the ace now ranks lowest
+        let rules = RuleSet::new("A23456789TJQK", "");
+        let game = Game::parse("A2345 1\n23456 2", rules).unwrap();
+        assert_eq!(game.winnings(), 5);
+
+        // two pairs beat three of a kind
+        let mut rules = RuleSet::standard();
+        let mut type_order = HandType::all(5);
+        type_order.swap(2, 3);
+        rules.type_order = Some(type_order);
+
+        let game = Game::parse("KKK23 1\nQQ22A 2", rules).unwrap();
+        assert_eq!(game.winnings(), 5);
+    }
+
+    #[test]
+    fn test_hand_sizes() {
+        assert_eq!(HandType::all(5).len(), 7);
+        assert_eq!(HandType::all(7).len(), 15);
+        assert_eq!(HandType::all(5)[4], HandType::new(&[3, 2]));
+        assert!(HandType::all(6).windows(2).all(|w| w[0] < w[1]));
+
+        let rules = RuleSet::jokers();
+        let hand_type = rules.hand_type(&['K', 'K', 'Q', 'Q', 'Q', 'J', '2']);
+        assert_eq!(hand_type.signature(), [4, 2, 1]);
+        assert_eq!(hand_type.cards(), 7);
+
+        let hand_type = rules.hand_type(&['J'; 6]);
+        assert_eq!(hand_type.signature(), [6]);
+
+        // from weakest to strongest: three pair, three of a kind, two
+        // triples, four of a kind
+        let game = Game::parse(
+            "KK3322 1\nAAAA23 2\nQQQ222 3\nAAA234 4",
+            RuleSet::standard(),
+        ).unwrap();
+
+        assert_eq!(game.winnings(), 1 + 4 * 2 + 3 * 3 + 2 * 4);
+    }
+
+    #[test]
+    fn test_sort_keys() {
+        let input = "32T3K 1\nT55J5 2\nKK677 3\nKTJJT 4\nQQQJA 5\nJJJJJ 6
+QQQJ 7\nQQQJAA 8\n2345 9\n23456 10\nAAAKKKQ 11";
+
+        let mut swapped = RuleSet::jokers();
+        let mut type_order = HandType::all(5);
+        type_order.swap(2, 3);
+        swapped.type_order = Some(type_order);
+
+        for rules in [RuleSet::standard(), RuleSet::jokers(), swapped] {
+            let game = Game::parse(input, rules).unwrap();
+
+            for l in game.hands() {
+                for r in game.hands() {
+                    assert_eq!(
+                        l.key.unwrap().cmp(&r.key.unwrap()),
+                        game.rules.compare(l, r),
+                    );
+                }
+            }
+        }
+
+        // hands too large to pack are still ranked
+        let long = "A".repeat(30);
+        let input = format!("{}2 1\n{}K 2", long, long);
+        let game = Game::parse(&input, RuleSet::standard()).unwrap();
+
+        assert!(game.hands()[0].key.is_none());
+        assert_eq!(game.winnings(), 1 + 2 * 2);
+    }
+
+    #[test]
+    fn test_equal_hands() {
+        // equal hands are ranked in input order, whether packed or not
+        let input = "AAAAA 2\nAAAAA 1";
+        let game = Game::parse(input, RuleSet::standard()).unwrap();
+        assert!(game.hands()[0].key.is_some());
+        assert_eq!(game.winnings(), 4);
+
+        let long = "A".repeat(30);
+        let input = format!("{} 2\n{} 1", long, long);
+        let game = Game::parse(&input, RuleSet::standard()).unwrap();
+        assert!(game.hands()[0].key.is_none());
+        assert_eq!(game.winnings(), 4);
+    }
+}
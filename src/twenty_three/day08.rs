@@ -1,6 +1,6 @@
 use crate::Solution;
-use std::clone::Clone;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use num::Integer;
 use num::integer::lcm;
 
 pub const SOLUTION: Solution<usize, usize> = Solution { part1, part2 };
@@ -8,113 +8,436 @@ pub const SOLUTION: Solution<usize, usize> = Solution { part1, part2 };
 pub const START: &str = "AAA";
 pub const END: &str = "ZZZ";
 
+/// The network of nodes, with every label interned to a dense index so that
+/// walking it only involves indexing into vectors.
 struct Graph {
-    nodes: HashMap<String, (String, String)>,
+    labels: Vec<String>,
+    ids: HashMap<String, u32>,
+    /// The left and right neighbour of every node.
+    adjacency: Vec<[u32; 2]>,
 }
 
 impl Graph {
-    fn left(&self, node: &str) -> Option<&str> {
-        Some(&self.nodes.get(node)?.0)
+    fn new() -> Self {
+        Self { labels: vec!(), ids: HashMap::new(), adjacency: vec!() }
     }
 
-    fn right(&self, node: &str) -> Option<&str> {
-        Some(&self.nodes.get(node)?.1)
+    /// Returns the index of the node with the given label, adding the node
+    /// if it is new. Its neighbours are unknown until it is connected.
+    fn intern(&mut self, label: &str) -> u32 {
+        if let Some(id) = self.ids.get(label) {
+            return *id;
+        }
+
+        let id = self.labels.len() as u32;
+
+        self.labels.push(label.to_owned());
+        self.ids.insert(label.to_owned(), id);
+        self.adjacency.push([u32::MAX; 2]);
+
+        id
+    }
+
+    fn connect(&mut self, node: u32, left: u32, right: u32) {
+        self.adjacency[node as usize] = [left, right];
+    }
+
+    fn id(&self, label: &str) -> Option<u32> {
+        self.ids.get(label).copied()
+    }
+
+    fn label(&self, node: u32) -> &str {
+        &self.labels[node as usize]
     }
 
-    fn filter_nodes<F>(&self, predicate: F) -> Vec<&str>
+    /// Returns the neighbour on the given side: 0 for left, 1 for right.
+    fn next(&self, node: u32, side: usize) -> u32 {
+        self.adjacency[node as usize][side]
+    }
+
+    fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    fn filter_nodes<F>(&self, predicate: F) -> Vec<u32>
         where F: Fn(&str) -> bool {
 
-        self
-            .nodes
-            .keys()
-            .filter(|label| predicate(label))
-            .map(|label| label.as_str())
+        (0..self.len() as u32)
+            .filter(|node| predicate(self.label(*node)))
             .collect()
     }
+
+    /// Returns for every node whether its label satisfies the predicate.
+    fn mark<F>(&self, predicate: F) -> Vec<bool>
+        where F: Fn(&str) -> bool {
+
+        self.labels.iter().map(|label| predicate(label)).collect()
+    }
 }
 
-struct DesertMap {
-    instructions: Vec<u8>,
+/// The path of a single ghost: after `tail` steps it enters a loop of
+/// `length` steps that it repeats forever. A step number is the number of
+/// moves made, so the ghost starts at step 0.
+#[derive(Debug, PartialEq, Eq)]
+struct Cycle {
+    tail: usize,
+    length: usize,
+    /// Steps before the loop at which the ghost is on a target node.
+    tail_hits: Vec<usize>,
+    /// Steps within the first pass through the loop at which the ghost is
+    /// on a target node. These recur every `length` steps.
+    offsets: Vec<usize>,
+}
+
+impl Cycle {
+    fn hits(&self, step: usize) -> bool {
+        if step < self.tail {
+            self.tail_hits.contains(&step)
+        }
+        else {
+            let offset = self.tail + (step - self.tail) % self.length;
+            self.offsets.contains(&offset)
+        }
+    }
+
+    /// Returns whether the ghost is on a target node at exactly the
+    /// multiples of its first arrival, which is all the lcm of the first
+    /// arrivals accounts for.
+    fn lcm_shortcut_holds(&self) -> bool {
+        let Some(first) = self.first_arrival() else {
+            return false;
+        };
+
+        // the loop may span several arrivals, as long as it repeats them
+        first > 0
+            && self.length.is_multiple_of(first)
+            && (1..self.tail + self.length)
+                .all(|step| self.hits(step) == step.is_multiple_of(first))
+    }
+
+    fn first_arrival(&self) -> Option<usize> {
+        self.tail_hits.first().or(self.offsets.first()).copied()
+    }
+}
+
+/// Combines t = r1 (mod m1) and t = r2 (mod m2) into a single congruence,
+/// if both can hold at once. The moduli need not be coprime.
+fn crt(
+    (r1, m1): (i128, i128),
+    (r2, m2): (i128, i128),
+) -> Option<(i128, i128)> {
+    let egcd = m1.extended_gcd(&m2);
+
+    if (r2 - r1) % egcd.gcd != 0 {
+        return None;
+    }
+
+    // m1 * x = gcd (mod m2), so stepping k * m1 from r1 reaches r2
+    let lcm = m1 / egcd.gcd * m2;
+    let k = ((r2 - r1) / egcd.gcd * egcd.x).rem_euclid(m2 / egcd.gcd);
+
+    Some(((r1 + m1 * k).rem_euclid(lcm), lcm))
+}
+
+/// When the ghosts first all stand on target nodes, and whether that could
+/// be found with the lcm of their first arrivals.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GhostMoves {
+    /// The number of moves, or `None` if the ghosts never meet.
+    pub moves: Option<usize>,
+    /// Whether every ghost reaches a target exactly at the multiples of
+    /// its first arrival. Otherwise the moves were found with the Chinese
+    /// remainder theorem.
+    pub lcm_shortcut: bool,
+}
+
+pub struct DesertMap {
+    /// The side to take at each step: 0 for left, 1 for right.
+    instructions: Vec<usize>,
     graph: Graph,
 }
 
 impl DesertMap {
-    fn moves<F>(
-        &self,
-        src: &str,
-        dst_pred: F
-    ) -> usize where F: Fn(&str) -> bool {
-        
+    /// Returns the labels of every node visited on the way from `src` to
+    /// the first node matching `dst_pred`, both included, or `None` if
+    /// `src` does not exist or the path never gets there.
+    pub fn path<F>(&self, src: &str, dst_pred: F) -> Option<Vec<&str>>
+        where F: Fn(&str) -> bool {
+
+        let targets = self.graph.mark(dst_pred);
+        let period = self.instructions.len();
+        let mut seen = vec![false; self.graph.len() * period];
+
+        let mut current = self.graph.id(src)?;
+        let mut path = vec!(current);
+
+        while !targets[current as usize] {
+            let step = path.len() - 1;
+            let state = current as usize * period + step % period;
+
+            // back in the same place with the same instructions ahead
+            if seen[state] {
+                return None;
+            }
+
+            seen[state] = true;
+
+            let side = self.instructions[step % period];
+
+            current = self.graph.next(current, side);
+            path.push(current);
+        }
+
+        Some(path.iter().map(|node| self.graph.label(*node)).collect())
+    }
+
+    /// Renders the network in Graphviz DOT format. Nodes ending in A are
+    /// drawn green and nodes ending in Z red, and the edges taken along
+    /// `path` are drawn in bold.
+    pub fn to_dot(&self, path: &[&str]) -> String {
+        let ids: Vec<_> = path
+            .iter()
+            .filter_map(|label| self.graph.id(label))
+            .collect();
+
+        let traversed: HashSet<_> = ids
+            .windows(2)
+            .map(|w| (w[0], w[1]))
+            .collect();
+
+        let quote = |node| {
+            let label = self.graph.label(node);
+            format!("\"{}\"", label.replace('\\', "\\\\").replace('"', "\\\""))
+        };
+
+        let mut dot = String::from("digraph desert {\n");
+
+        for node in 0..self.graph.len() as u32 {
+            let label = self.graph.label(node);
+
+            let colour = if label.ends_with('A') {
+                " [color=green]"
+            }
+            else if label.ends_with('Z') {
+                " [color=red]"
+            }
+            else {
+                ""
+            };
+
+            dot.push_str(&format!("    {}{};\n", quote(node), colour));
+
+            for (side, direction) in ["L", "R"].iter().enumerate() {
+                let next = self.graph.next(node, side);
+
+                let style = if traversed.contains(&(node, next)) {
+                    ", style=bold"
+                }
+                else {
+                    ""
+                };
+
+                dot.push_str(&format!(
+                    "    {} -> {} [label={}{}];\n",
+                    quote(node),
+                    quote(next),
+                    direction,
+                    style,
+                ));
+            }
+        }
+
+        dot.push_str("}\n");
+
+        dot
+    }
+
+    fn moves(&self, src: u32, targets: &[bool]) -> usize {
         let mut current = src;
         let mut moves = 0;
 
-        while !dst_pred(current) {
-            current = match self.instructions[moves % self.instructions.len()] {
-                b'L' => self.graph.left(current).unwrap(),
-                b'R' => self.graph.right(current).unwrap(),
-                _ => panic!(),
-            };
+        while !targets[current as usize] {
+            let side = self.instructions[moves % self.instructions.len()];
 
+            current = self.graph.next(current, side);
             moves += 1;
         }
 
         moves
     }
 
-    fn moves_from_predicated<F, G>(
-        &self,
-        src_pred: F,
-        dst_pred: G,
-    ) -> Vec<usize>
-    where F: Fn(&str) -> bool + Clone, G: Fn(&str) -> bool + Clone {
-        let sources: Vec<_> = self.graph.filter_nodes(src_pred.clone());
-            
-        sources
+    /// Follows the instructions from `src` until the ghost is in the same
+    /// node at the same point in the instructions for the second time.
+    fn cycle(&self, src: u32, targets: &[bool]) -> Cycle {
+        let period = self.instructions.len();
+        let mut seen = vec![usize::MAX; self.graph.len() * period];
+        let mut hits = vec!();
+
+        let mut current = src;
+        let mut step = 0;
+
+        let tail = loop {
+            let state = current as usize * period + step % period;
+
+            if seen[state] != usize::MAX {
+                break seen[state];
+            }
+
+            seen[state] = step;
+
+            if targets[current as usize] {
+                hits.push(step);
+            }
+
+            let side = self.instructions[step % period];
+
+            current = self.graph.next(current, side);
+            step += 1;
+        };
+
+        let (tail_hits, offsets) = hits
+            .into_iter()
+            .partition(|hit| *hit < tail);
+
+        Cycle { tail, length: step - tail, tail_hits, offsets }
+    }
+
+    /// Returns the number of moves after which ghosts starting at every
+    /// node matching `src_pred` are all on nodes matching `dst_pred`.
+    pub fn ghost_moves<F, G>(&self, src_pred: F, dst_pred: G) -> GhostMoves
+    where F: Fn(&str) -> bool, G: Fn(&str) -> bool {
+        let targets = self.graph.mark(dst_pred);
+
+        let cycles: Vec<_> = self
+            .graph
+            .filter_nodes(src_pred)
             .iter()
-            .map(|src| self.moves(src, dst_pred.clone()))
-            .collect::<Vec<_>>()
+            .map(|src| self.cycle(*src, &targets))
+            .collect();
+
+        if cycles.iter().all(Cycle::lcm_shortcut_holds) {
+            let moves = cycles
+                .iter()
+                .filter_map(Cycle::first_arrival)
+                .fold(1, lcm);
+
+            return GhostMoves { moves: Some(moves), lcm_shortcut: true };
+        }
+
+        GhostMoves {
+            moves: Self::earliest_meeting(&cycles),
+            lcm_shortcut: false,
+        }
+    }
+
+    /// Returns when the ghosts starting on nodes ending in A all stand on
+    /// nodes ending in Z, as in part 2.
+    pub fn ghosts(&self) -> GhostMoves {
+        self.ghost_moves(|node| node.ends_with('A'), |node| node.ends_with('Z'))
+    }
+
+    fn earliest_meeting(cycles: &[Cycle]) -> Option<usize> {
+        let tail = cycles.iter().map(|c| c.tail).max().unwrap_or(0);
+
+        // until every ghost is in its loop, check each step directly
+        if let Some(step) = (0..tail)
+            .find(|step| cycles.iter().all(|c| c.hits(*step))) {
+
+            return Some(step);
+        }
+
+        // afterwards, every combination of offsets is a candidate
+        let mut residues = vec!((0, 1));
+
+        for cycle in cycles {
+            let length = cycle.length as i128;
+
+            residues = residues
+                .iter()
+                .flat_map(|r| cycle
+                    .offsets
+                    .iter()
+                    .filter_map(move |o| crt(*r, (*o as i128, length)))
+                )
+                .collect();
+        }
+
+        let tail = tail as i128;
+
+        residues
+            .iter()
+            .map(|(r, m)| tail + (r - tail).rem_euclid(*m))
+            .min()
+            .map(|step| step as usize)
     }
 }
 
-impl From<&str> for DesertMap {
-    fn from(input: &str) -> Self {
-        let (instructions, graph_desc) = input
+impl TryFrom<&str> for DesertMap {
+    type Error = ();
+
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        let (instructions_str, graph_desc) = input
             .split_once("\n\n")
-            .map(|(l, r)| (l.as_bytes().to_vec(), r))
-            .unwrap();
+            .ok_or(())?;
+
+        let instructions = instructions_str
+            .trim()
+            .chars()
+            .map(|c| match c {
+                'L' => Ok(0),
+                'R' => Ok(1),
+                _ => Err(()),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
 
-        let nodes = graph_desc
-            .lines()
-            .map(|l| (l[0..3].to_owned(), (l[7..10].to_owned(), l[12..15].to_owned())))
-            .collect::<HashMap<_, _>>();
+        if instructions.is_empty() {
+            return Err(());
+        }
+
+        let mut graph = Graph::new();
+
+        for line in graph_desc.lines() {
+            let (node, neighbours) = line.split_once(" = ").ok_or(())?;
 
-        let graph = Graph { nodes };
+            let (left, right) = neighbours
+                .strip_prefix('(')
+                .and_then(|n| n.strip_suffix(')'))
+                .and_then(|n| n.split_once(", "))
+                .ok_or(())?;
 
-        Self { instructions, graph }
+            let node = graph.intern(node.trim());
+            let left = graph.intern(left);
+            let right = graph.intern(right);
+
+            graph.connect(node, left, right);
+        }
+
+        // every node that is referred to must lead somewhere
+        if graph.adjacency.iter().any(|next| next.contains(&u32::MAX)) {
+            return Err(());
+        }
+
+        Ok(Self { instructions, graph })
     }
 }
 
 fn part1(input: &str) -> usize {
-    let desert_map = DesertMap::from(input);
-    
-    desert_map.moves(START, |node| node == END)
+    let desert_map = DesertMap::try_from(input).unwrap();
+    let start = desert_map.graph.id(START).unwrap();
+    let targets = desert_map.graph.mark(|node| node == END);
+
+    desert_map.moves(start, &targets)
 }
 
 fn part2(input: &str) -> usize {
-    let desert_map = DesertMap::from(input);
-
-    desert_map
-        .moves_from_predicated(
-            |node: &str| node.ends_with('A'),
-            |node: &str| node.ends_with('Z')
-        )
-        .iter()
-        .fold(1, |moves, acc| lcm(moves, *acc))
+    let desert_map = DesertMap::try_from(input).unwrap();
+
+    desert_map.ghosts().moves.unwrap()
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{part1, part2};
+    use super::{crt, part1, part2, Cycle, DesertMap, END, START};
 
     const TEST_INPUT_1: &str = "RL
 
@@ -153,5 +476,103 @@ XXX = (XXX, XXX)";
     fn test_part2() {
         assert_eq!(part2(TEST_INPUT_3), 6);
     }
-}
 
+    #[test]
+    fn test_labels() {
+        let desert_map = DesertMap::try_from("LLR
+
+START = (left, ZZZ)
+left = (START, START)
+ZZZ = (ZZZ, ZZZ)").unwrap();
+
+        let graph = &desert_map.graph;
+        let start = graph.id("START").unwrap();
+
+        assert_eq!(graph.len(), 3);
+        assert_eq!(graph.label(graph.next(start, 0)), "left");
+        assert_eq!(desert_map.moves(start, &graph.mark(|l| l == "ZZZ")), 3);
+
+        // nodes that are referred to but never described
+        assert!(DesertMap::try_from("L\n\nAAA = (BBB, AAA)").is_err());
+        assert!(DesertMap::try_from("LX\n\nAAA = (AAA, AAA)").is_err());
+    }
+
+    const TEST_INPUT_4: &str = "L
+
+11A = (11B, 11B)
+11B = (11Z, 11Z)
+11Z = (11B, 11B)
+22A = (22Z, 22Z)
+22Z = (22B, 22B)
+22B = (22C, 22C)
+22C = (22Z, 22Z)";
+
+    #[test]
+    fn test_cycles() {
+        let desert_map = DesertMap::try_from(TEST_INPUT_4).unwrap();
+        let graph = &desert_map.graph;
+        let targets = graph.mark(|l| l.ends_with('Z'));
+
+        let cycle = desert_map.cycle(graph.id("22A").unwrap(), &targets);
+
+        assert_eq!(cycle, Cycle {
+            tail: 1,
+            length: 3,
+            tail_hits: vec!(),
+            offsets: vec!(1),
+        });
+
+        assert!(!cycle.lcm_shortcut_holds());
+        assert!(!desert_map.ghosts().lcm_shortcut);
+
+        let desert_map = DesertMap::try_from(TEST_INPUT_3).unwrap();
+        assert!(desert_map.ghosts().lcm_shortcut);
+
+        // the first ghost is on a Z node at even steps, the second one at
+        // steps 1, 4, 7, ..., so the lcm of their first arrivals is wrong
+        assert_eq!(part2(TEST_INPUT_4), 4);
+
+        // the second ghost only ever passes 22Z once
+        let input = TEST_INPUT_4.replace("C = (22Z, 22Z)", "C = (22B, 22B)");
+        let desert_map = DesertMap::try_from(input.as_str()).unwrap();
+        assert_eq!(desert_map.ghosts().moves, None);
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt((2, 3), (3, 5)), Some((8, 15)));
+        assert_eq!(crt((0, 4), (2, 6)), Some((8, 12)));
+        assert_eq!(crt((1, 4), (2, 6)), None);
+    }
+
+    #[test]
+    fn test_path() {
+        let desert_map = DesertMap::try_from(TEST_INPUT_2).unwrap();
+        let path = desert_map.path(START, |l| l == END).unwrap();
+
+        assert_eq!(path, ["AAA", "BBB", "AAA", "BBB", "AAA", "BBB", "ZZZ"]);
+
+        let desert_map = DesertMap::try_from(TEST_INPUT_1).unwrap();
+        assert_eq!(desert_map.path("BBB", |l| l == END), None);
+        assert_eq!(desert_map.path("YYY", |l| l == END), None);
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let desert_map = DesertMap::try_from(TEST_INPUT_2).unwrap();
+        let path = desert_map.path(START, |l| l == END).unwrap();
+
+        assert_eq!(desert_map.to_dot(&path), "digraph desert {
+    \"AAA\" [color=green];
+    \"AAA\" -> \"BBB\" [label=L, style=bold];
+    \"AAA\" -> \"BBB\" [label=R, style=bold];
+    \"BBB\";
+    \"BBB\" -> \"AAA\" [label=L, style=bold];
+    \"BBB\" -> \"ZZZ\" [label=R, style=bold];
+    \"ZZZ\" [color=red];
+    \"ZZZ\" -> \"ZZZ\" [label=L];
+    \"ZZZ\" -> \"ZZZ\" [label=R];
+}
+");
+    }
+}
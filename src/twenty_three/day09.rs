@@ -4,8 +4,8 @@ use crate::Solution;
 
 pub const SOLUTION: Solution<isize, isize> = Solution { part1, part2 };
 
-struct Sensor {
-    readings: Vec<isize>,
+pub struct Sensor {
+    pub readings: Vec<isize>,
 }
 
 impl From<&str> for Sensor {
@@ -19,59 +19,177 @@ impl From<&str> for Sensor {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExtrapolationError {
+    /// Taking differences never produced a row of zeros, so the readings
+    /// are not known to follow a polynomial.
+    NoZeroRow,
+    /// The differences do not fit in an `i128`.
+    Overflow,
+    /// There are not enough readings to fit a polynomial of the requested
+    /// degree.
+    TooFewReadings,
+}
+
+/// The polynomial that the readings of a sensor follow, in Newton's forward
+/// difference form: the value at reading x is the sum of
+/// `coefficients[k] * binomial(x, k)`, where x = 0 is the first reading.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Fit {
+    pub degree: usize,
+    /// The first entry of every row of the difference table, down to the
+    /// last row that is not all zeros.
+    pub coefficients: Vec<i128>,
+    readings: usize,
+}
+
+impl Fit {
+    fn new(mut coefficients: Vec<i128>, readings: usize) -> Self {
+        // higher differences that are zero do not add to the degree
+        while coefficients.len() > 1 && coefficients.last() == Some(&0) {
+            coefficients.pop();
+        }
+
+        if coefficients.is_empty() {
+            coefficients.push(0);
+        }
+
+        Self { degree: coefficients.len() - 1, coefficients, readings }
+    }
+
+    /// Returns the value at reading `x`, which may lie before the first or
+    /// after the last reading, or `None` if it does not fit in an `isize`.
+    pub fn at(&self, x: i128) -> Option<isize> {
+        let mut binomial: i128 = 1;
+        let mut value: i128 = 0;
+
+        for (k, coefficient) in self.coefficients.iter().enumerate() {
+            // binomial(x, k) * (x - k) = binomial(x, k + 1) * (k + 1)
+            if k > 0 {
+                let k = k as i128;
+                binomial = binomial.checked_mul(x - k + 1)? / k;
+            }
+
+            value = value.checked_add(coefficient.checked_mul(binomial)?)?;
+        }
+
+        value.try_into().ok()
+    }
+
+    /// Predicts the value `k` steps after the last reading.
+    pub fn predict(&self, k: usize) -> Option<isize> {
+        self.at(self.readings as i128 - 1 + k as i128)
+    }
+
+    /// Predicts the value `k` steps before the first reading.
+    pub fn predict_backwards(&self, k: usize) -> Option<isize> {
+        self.at(-(k as i128))
+    }
+}
+
 impl Sensor {
-    fn _extrapolate(readings: &[isize]) -> isize {
-        if readings.iter().all(|r| *r == 0) {
-            return 0;
+    fn first_row(&self) -> Vec<i128> {
+        self.readings.iter().map(|reading| *reading as i128).collect()
+    }
+
+    /// Replaces a row of the difference table by the row below it.
+    fn difference(row: &mut Vec<i128>) -> Result<(), ExtrapolationError> {
+        for i in 0..row.len() - 1 {
+            row[i] = row[i + 1]
+                .checked_sub(row[i])
+                .ok_or(ExtrapolationError::Overflow)?;
         }
 
-        let deltas = readings
-            .windows(2)
-            .map(|pair| pair[1] - pair[0])
-            .collect::<Vec<_>>();
+        row.pop();
 
-        readings[readings.len() - 1] + Self::_extrapolate(deltas.as_slice())
+        Ok(())
     }
 
-    fn extrapolate(&self) -> isize {
-        Self::_extrapolate(self.readings.as_slice())
+    /// Fits a polynomial to the readings by building their difference
+    /// table, one row at a time in a single buffer. A row of zeros must be
+    /// reached before the table runs out of rows, so at least one reading
+    /// more than the degree is needed to trust the fit.
+    pub fn fit(&self) -> Result<Fit, ExtrapolationError> {
+        let mut row = self.first_row();
+        let mut coefficients = vec!();
+
+        while !row.iter().all(|r| *r == 0) {
+            if row.len() < 2 {
+                return Err(ExtrapolationError::NoZeroRow);
+            }
+
+            coefficients.push(row[0]);
+            Self::difference(&mut row)?;
+        }
+
+        if row.is_empty() {
+            return Err(ExtrapolationError::NoZeroRow);
+        }
+
+        Ok(Fit::new(coefficients, self.readings.len()))
     }
 
-    fn _extrapolate_backwards(readings: &[isize]) -> isize {
-        if readings.iter().all(|r| *r == 0) {
-            return 0;
+    /// Fits a polynomial of at most the given degree, trusting that the
+    /// readings follow one instead of checking for a row of zeros. Only
+    /// the first `degree + 1` rows of the difference table are built.
+    pub fn fit_degree(
+        &self,
+        degree: usize,
+    ) -> Result<Fit, ExtrapolationError> {
+        if self.readings.len() <= degree {
+            return Err(ExtrapolationError::TooFewReadings);
         }
 
-        let deltas = readings
-            .windows(2)
-            .map(|pair| pair[1] - pair[0])
-            .collect::<Vec<_>>();
+        let mut row = self.first_row();
+        let mut coefficients = vec!();
+
+        for _ in 0..=degree {
+            coefficients.push(row[0]);
+            Self::difference(&mut row)?;
+        }
 
-        readings[0] - Self::_extrapolate_backwards(deltas.as_slice())
+        Ok(Fit::new(coefficients, self.readings.len()))
     }
 
-    fn extrapolate_backwards(&self) -> isize {
-        Self::_extrapolate_backwards(self.readings.as_slice())
+    /// Fits the polynomial through all readings, which is what taking
+    /// differences until a row is empty amounts to.
+    pub fn interpolate(&self) -> Result<Fit, ExtrapolationError> {
+        self.fit_degree(self.readings.len().saturating_sub(1))
     }
 }
 
+/// Fits the readings on a line. Sequences too short to reach a row of
+/// zeros are fitted through all their readings instead, as taking
+/// differences until nothing is left would, and lines without readings
+/// yield `None`.
+fn fit_line(line: &str) -> Option<Fit> {
+    let sensor = Sensor::from(line);
+
+    sensor
+        .fit()
+        .or_else(|_| sensor.interpolate())
+        .ok()
+}
+
 fn part1(input: &str) -> isize {
     input
         .lines()
-        .map(|l| Sensor::from(l).extrapolate())
+        .filter_map(fit_line)
+        .map(|fit| fit.predict(1).unwrap())
         .sum()
 }
 
 fn part2(input: &str) -> isize {
     input
         .lines()
-        .map(|l| Sensor::from(l).extrapolate_backwards())
+        .filter_map(fit_line)
+        .map(|fit| fit.predict_backwards(1).unwrap())
         .sum()
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{part1, part2};
+    use super::{part1, part2, ExtrapolationError, Sensor};
 
     const TEST_INPUT: &str = "0 3 6 9 12 15
 1 3 6 10 15 21
@@ -86,5 +204,48 @@ mod tests {
     fn test_part2() {
         assert_eq!(part2(TEST_INPUT), 2);
     }
-}
 
+    #[test]
+    fn test_fit() {
+        let fit = Sensor::from("10 13 16 21 30 45").fit().unwrap();
+
+        assert_eq!(fit.degree, 3);
+        assert_eq!(fit.coefficients, vec!(10, 3, 0, 2));
+        assert_eq!(fit.predict(1), Some(68));
+        assert_eq!(fit.predict(2), Some(101));
+        assert_eq!(fit.predict_backwards(1), Some(5));
+        assert_eq!(fit.predict_backwards(3), Some(-19));
+        assert_eq!(fit.at(2), Some(16));
+
+        let fit = Sensor::from("0 0 0").fit().unwrap();
+        assert_eq!((fit.degree, fit.predict(5)), (0, Some(0)));
+
+        // x^3 through four points leaves no room for a zero row
+        assert_eq!(
+            Sensor::from("0 1 8 27").fit(),
+            Err(ExtrapolationError::NoZeroRow)
+        );
+
+        // the next value no longer fits in an isize
+        let step = isize::MAX / 2;
+        let line = format!("0 {} {}", step, 2 * step);
+        let fit = Sensor::from(line.as_str()).fit().unwrap();
+        assert_eq!(fit.predict(1), None);
+    }
+
+    #[test]
+    fn test_short_sequences() {
+        let sensor = Sensor::from("1 2");
+        assert_eq!(sensor.fit(), Err(ExtrapolationError::NoZeroRow));
+        assert_eq!(sensor.interpolate().unwrap().predict(1), Some(3));
+
+        assert_eq!(
+            Sensor::from("0 1 4").fit_degree(3),
+            Err(ExtrapolationError::TooFewReadings)
+        );
+
+        // the empty line is skipped
+        assert_eq!(part1("1 2\n5\n\n0 1 4"), 3 + 5 + 9);
+        assert_eq!(part2("1 2\n5\n\n0 1 4"), 5 + 1);
+    }
+}
@@ -0,0 +1,204 @@
+use crate::Solution;
+use crate::pathfinding::dijkstra;
+
+pub const SOLUTION: Solution<usize, usize> = Solution { part1, part2 };
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+use Direction::{*};
+
+impl Direction {
+    fn turns(&self) -> [Direction; 2] {
+        match self {
+            North | South => [East, West],
+            East | West => [North, South],
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct Position {
+    x: isize,
+    y: isize,
+}
+
+impl Position {
+    fn neighbour(&self, dir: Direction) -> Self {
+        let (x, y) = match dir {
+            North => (self.x, self.y - 1),
+            East => (self.x + 1, self.y),
+            South => (self.x, self.y + 1),
+            West => (self.x - 1, self.y),
+        };
+
+        Self { x, y }
+    }
+}
+
+/// A crucible's search state: where it is, which way it is heading and how
+/// many blocks it has moved in a straight line so far.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct State {
+    pos: Position,
+    dir: Direction,
+    run: usize,
+}
+
+struct Crucible {
+    min_run: usize,
+    max_run: usize,
+}
+
+impl Crucible {
+    const REGULAR: Crucible = Crucible { min_run: 1, max_run: 3 };
+    const ULTRA: Crucible = Crucible { min_run: 4, max_run: 10 };
+
+    fn can_turn(&self, state: &State) -> bool {
+        state.run >= self.min_run
+    }
+
+    fn can_continue(&self, state: &State) -> bool {
+        state.run < self.max_run
+    }
+}
+
+struct City {
+    rows: Vec<Vec<u8>>,
+}
+
+impl City {
+    fn width(&self) -> usize {
+        if self.rows.is_empty() {
+            return 0;
+        }
+
+        self.rows[0].len()
+    }
+
+    fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    fn validate_pos(&self, pos: &Position) -> bool {
+        pos.x >= 0
+            && pos.x < self.width() as isize
+            && pos.y >= 0
+            && pos.y < self.height() as isize
+    }
+
+    fn heat_loss(&self, pos: &Position) -> usize {
+        self.rows[pos.y as usize][pos.x as usize] as usize
+    }
+
+    fn next_states(&self, crucible: &Crucible, state: &State) -> Vec<(State, usize)> {
+        let mut next_states = vec!();
+
+        if crucible.can_continue(state) {
+            let pos = state.pos.neighbour(state.dir);
+            next_states.push(State { pos, dir: state.dir, run: state.run + 1 });
+        }
+
+        if crucible.can_turn(state) {
+            for dir in state.dir.turns() {
+                let pos = state.pos.neighbour(dir);
+                next_states.push(State { pos, dir, run: 1 });
+            }
+        }
+
+        next_states
+            .into_iter()
+            .filter(|next| self.validate_pos(&next.pos))
+            .map(|next| (next, self.heat_loss(&next.pos)))
+            .collect()
+    }
+
+    /// Returns the least heat loss incurred when moving the crucible from the
+    /// top-left to the bottom-right city block.
+    fn min_heat_loss(&self, crucible: &Crucible) -> Option<usize> {
+        let origin = Position { x: 0, y: 0 };
+        let factory = Position {
+            x: self.width() as isize - 1,
+            y: self.height() as isize - 1,
+        };
+
+        // the crucible may initially head either east or south
+        let starts = [East, South].map(|dir| State { pos: origin, dir, run: 0 });
+
+        dijkstra(
+            starts,
+            |state| self.next_states(crucible, state),
+            |state| state.pos == factory && crucible.can_turn(state),
+        )
+    }
+}
+
+impl TryFrom<&str> for City {
+    type Error = ();
+
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        let rows: Option<Vec<Vec<_>>> = input
+            .lines()
+            .map(|l| l
+                 .chars()
+                 .map(|c| c.to_digit(10).map(|d| d as u8))
+                 .collect()
+            )
+            .collect();
+
+        Ok(Self { rows: rows.ok_or(())? })
+    }
+}
+
+fn part1(input: &str) -> usize {
+    let city = City::try_from(input).unwrap();
+
+    city.min_heat_loss(&Crucible::REGULAR).unwrap()
+}
+
+fn part2(input: &str) -> usize {
+    let city = City::try_from(input).unwrap();
+
+    city.min_heat_loss(&Crucible::ULTRA).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{part1, part2};
+
+    const TEST_INPUT_1: &str = "2413432311323
+3215453535623
+3255245654254
+3446585845452
+4546657867536
+1438598798454
+4457876987766
+3637877979653
+4654967986887
+4564679986453
+1224686865563
+2546548887735
+4322674655533";
+
+    const TEST_INPUT_2: &str = "111111111111
+999999999991
+999999999991
+999999999991
+999999999991";
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(TEST_INPUT_1), 102);
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(TEST_INPUT_1), 94);
+        assert_eq!(part2(TEST_INPUT_2), 71);
+    }
+}
@@ -0,0 +1,151 @@
+use crate::Solution;
+use crate::polygon::{Point, Polygon};
+
+pub const SOLUTION: Solution<i64, i64> = Solution { part1, part2 };
+
+#[derive(Clone, Copy)]
+enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+use Direction::{*};
+
+impl TryFrom<&str> for Direction {
+    type Error = ();
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        match s {
+            "U" | "3" => Ok(Up),
+            "R" | "0" => Ok(Right),
+            "D" | "1" => Ok(Down),
+            "L" | "2" => Ok(Left),
+            _ => Err(()),
+        }
+    }
+}
+
+struct Instruction {
+    dir: Direction,
+    distance: i64,
+}
+
+impl Instruction {
+    /// Parses the instruction as written in the dig plan's first two columns.
+    fn from_plan(line: &str) -> Result<Self, ()> {
+        let mut fields = line.split_whitespace();
+
+        let dir = Direction::try_from(fields.next().ok_or(())?)?;
+        let distance = fields
+            .next()
+            .ok_or(())?
+            .parse()
+            .or(Err(()))?;
+
+        Ok(Self { dir, distance })
+    }
+
+    /// Decodes the instruction hidden in the dig plan's colour code, where
+    /// the first five hex digits give the distance and the last one the
+    /// direction.
+    fn from_colour(line: &str) -> Result<Self, ()> {
+        let hex = line
+            .split_once("(#")
+            .and_then(|(_, r)| r.strip_suffix(')'))
+            .filter(|hex| hex.len() == 6)
+            .ok_or(())?;
+
+        let distance = i64::from_str_radix(&hex[..5], 16).or(Err(()))?;
+        let dir = Direction::try_from(&hex[5..])?;
+
+        Ok(Self { dir, distance })
+    }
+}
+
+struct DigPlan {
+    instructions: Vec<Instruction>,
+}
+
+impl DigPlan {
+    fn parse<F>(input: &str, parse_line: F) -> Result<Self, ()>
+        where F: Fn(&str) -> Result<Instruction, ()> {
+
+        let instructions = input
+            .lines()
+            .map(parse_line)
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { instructions })
+    }
+
+    fn trench(&self) -> Polygon {
+        let mut pos = Point { x: 0, y: 0 };
+
+        let vertices = self
+            .instructions
+            .iter()
+            .map(|instr| {
+                match instr.dir {
+                    Up => pos.y -= instr.distance,
+                    Right => pos.x += instr.distance,
+                    Down => pos.y += instr.distance,
+                    Left => pos.x -= instr.distance,
+                }
+
+                pos
+            })
+            .collect();
+
+        Polygon::new(vertices)
+    }
+
+    /// Returns the number of cubic metres of lava the lagoon can hold: the
+    /// trench itself plus its dug-out interior.
+    fn lagoon_volume(&self) -> i64 {
+        self.trench().enclosed_points()
+    }
+}
+
+fn part1(input: &str) -> i64 {
+    DigPlan::parse(input, Instruction::from_plan)
+        .unwrap()
+        .lagoon_volume()
+}
+
+fn part2(input: &str) -> i64 {
+    DigPlan::parse(input, Instruction::from_colour)
+        .unwrap()
+        .lagoon_volume()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{part1, part2};
+
+    const TEST_INPUT: &str = "R 6 (#70c710)
+D 5 (#0dc571)
+L 2 (#5713f0)
+D 2 (#d2c081)
+R 2 (#59c680)
+D 2 (#411b91)
+L 5 (#8ceee2)
+U 2 (#caa173)
+L 1 (#1b58a2)
+U 2 (#caa171)
+R 2 (#7807d2)
+U 3 (#a77fa3)
+L 2 (#015232)
+U 2 (#7a21e3)";
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(TEST_INPUT), 62);
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(TEST_INPUT), 952408144115);
+    }
+}
@@ -0,0 +1,343 @@
+use crate::Solution;
+use std::collections::HashMap;
+use std::ops::Range;
+
+pub const SOLUTION: Solution<u64, u64> = Solution { part1, part2 };
+
+const START: &str = "in";
+const MIN_RATING: u64 = 1;
+const MAX_RATING: u64 = 4000;
+
+#[derive(Clone, Copy)]
+enum Category {
+    X,
+    M,
+    A,
+    S,
+}
+
+impl TryFrom<&str> for Category {
+    type Error = ();
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        match s {
+            "x" => Ok(Category::X),
+            "m" => Ok(Category::M),
+            "a" => Ok(Category::A),
+            "s" => Ok(Category::S),
+            _ => Err(()),
+        }
+    }
+}
+
+enum Target {
+    Accept,
+    Reject,
+    Workflow(String),
+}
+
+impl From<&str> for Target {
+    fn from(s: &str) -> Self {
+        match s {
+            "A" => Target::Accept,
+            "R" => Target::Reject,
+            _ => Target::Workflow(s.to_owned()),
+        }
+    }
+}
+
+enum Condition {
+    LessThan(Category, u64),
+    GreaterThan(Category, u64),
+}
+
+impl Condition {
+    fn matches(&self, part: &Part) -> bool {
+        match *self {
+            Condition::LessThan(cat, value) => part.rating(cat) < value,
+            Condition::GreaterThan(cat, value) => part.rating(cat) > value,
+        }
+    }
+
+    /// Splits a range of parts into the subrange matching this condition and
+    /// the subrange that does not, either of which may be empty.
+    fn split(&self, parts: &PartRange) -> (PartRange, PartRange) {
+        let (cat, pivot) = match *self {
+            Condition::LessThan(cat, value) => (cat, value),
+            Condition::GreaterThan(cat, value) => (cat, value + 1),
+        };
+
+        let range = parts.range(cat);
+        let pivot = pivot.clamp(range.start, range.end);
+
+        let lower = parts.with_range(cat, range.start..pivot);
+        let upper = parts.with_range(cat, pivot..range.end);
+
+        match self {
+            Condition::LessThan(..) => (lower, upper),
+            Condition::GreaterThan(..) => (upper, lower),
+        }
+    }
+}
+
+enum Rule {
+    Conditional(Condition, Target),
+    Fallback(Target),
+}
+
+impl TryFrom<&str> for Rule {
+    type Error = ();
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        let Some((condition_str, target_str)) = s.split_once(':') else {
+            return Ok(Rule::Fallback(Target::from(s)));
+        };
+
+        let category = Category::try_from(condition_str.get(0..1).ok_or(())?)?;
+        let value = condition_str
+            .get(2..)
+            .ok_or(())?
+            .parse()
+            .or(Err(()))?;
+
+        let condition = match condition_str.as_bytes().get(1) {
+            Some(b'<') => Condition::LessThan(category, value),
+            Some(b'>') => Condition::GreaterThan(category, value),
+            _ => return Err(()),
+        };
+
+        Ok(Rule::Conditional(condition, Target::from(target_str)))
+    }
+}
+
+struct Workflow {
+    rules: Vec<Rule>,
+}
+
+impl Workflow {
+    fn target(&self, part: &Part) -> &Target {
+        for rule in &self.rules {
+            match rule {
+                Rule::Conditional(condition, target) => {
+                    if condition.matches(part) {
+                        return target;
+                    }
+                },
+                Rule::Fallback(target) => return target,
+            }
+        }
+
+        panic!("Workflow without fallback rule");
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Part {
+    ratings: [u64; 4],
+}
+
+impl Part {
+    fn rating(&self, cat: Category) -> u64 {
+        self.ratings[cat as usize]
+    }
+
+    fn total_rating(&self) -> u64 {
+        self.ratings.iter().sum()
+    }
+}
+
+impl TryFrom<&str> for Part {
+    type Error = ();
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        let mut ratings = [0; 4];
+
+        let fields = s
+            .strip_prefix('{')
+            .and_then(|s| s.strip_suffix('}'))
+            .ok_or(())?
+            .split(',');
+
+        for field in fields {
+            let (cat_str, value_str) = field.split_once('=').ok_or(())?;
+            let cat = Category::try_from(cat_str)?;
+
+            ratings[cat as usize] = value_str.parse().or(Err(()))?;
+        }
+
+        Ok(Self { ratings })
+    }
+}
+
+/// A four-dimensional box of parts, holding one half-open rating range for
+/// each category.
+#[derive(Clone)]
+struct PartRange {
+    ranges: [Range<u64>; 4],
+}
+
+impl PartRange {
+    fn full() -> Self {
+        let range = MIN_RATING..MAX_RATING + 1;
+
+        Self { ranges: [range.clone(), range.clone(), range.clone(), range] }
+    }
+
+    fn range(&self, cat: Category) -> Range<u64> {
+        self.ranges[cat as usize].clone()
+    }
+
+    fn with_range(&self, cat: Category, range: Range<u64>) -> Self {
+        let mut ranges = self.ranges.clone();
+        ranges[cat as usize] = range;
+
+        Self { ranges }
+    }
+
+    fn combinations(&self) -> u64 {
+        self
+            .ranges
+            .iter()
+            .map(|range| range.end.saturating_sub(range.start))
+            .product()
+    }
+}
+
+struct System {
+    workflows: HashMap<String, Workflow>,
+    parts: Vec<Part>,
+}
+
+impl System {
+    fn accepts(&self, part: &Part) -> bool {
+        let mut workflow = &self.workflows[START];
+
+        loop {
+            match workflow.target(part) {
+                Target::Accept => return true,
+                Target::Reject => return false,
+                Target::Workflow(label) => workflow = &self.workflows[label],
+            }
+        }
+    }
+
+    /// Counts the rating combinations within `parts` that end up accepted
+    /// when entering at `target`, by splitting the box at every rule rather
+    /// than evaluating individual parts.
+    fn accepted_combinations(&self, target: &Target, parts: PartRange) -> u64 {
+        let workflow = match target {
+            Target::Accept => return parts.combinations(),
+            Target::Reject => return 0,
+            Target::Workflow(label) => &self.workflows[label],
+        };
+
+        let mut remaining = parts;
+        let mut accepted = 0;
+
+        for rule in &workflow.rules {
+            if remaining.combinations() == 0 {
+                break;
+            }
+
+            match rule {
+                Rule::Conditional(condition, target) => {
+                    let (matching, rest) = condition.split(&remaining);
+
+                    accepted += self.accepted_combinations(target, matching);
+                    remaining = rest;
+                },
+                Rule::Fallback(target) => {
+                    accepted += self.accepted_combinations(target, remaining);
+                    break;
+                },
+            }
+        }
+
+        accepted
+    }
+}
+
+impl TryFrom<&str> for System {
+    type Error = ();
+
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        let (workflows_str, parts_str) = input.split_once("\n\n").ok_or(())?;
+
+        let workflows = workflows_str
+            .lines()
+            .map(|l| {
+                let (label, rules_str) = l
+                    .strip_suffix('}')
+                    .and_then(|l| l.split_once('{'))
+                    .ok_or(())?;
+
+                let rules = rules_str
+                    .split(',')
+                    .map(Rule::try_from)
+                    .collect::<Result<_, _>>()?;
+
+                Ok((label.to_owned(), Workflow { rules }))
+            })
+            .collect::<Result<_, _>>()?;
+
+        let parts = parts_str
+            .lines()
+            .map(Part::try_from)
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { workflows, parts })
+    }
+}
+
+fn part1(input: &str) -> u64 {
+    let system = System::try_from(input).unwrap();
+
+    system
+        .parts
+        .iter()
+        .filter(|part| system.accepts(part))
+        .map(|part| part.total_rating())
+        .sum()
+}
+
+fn part2(input: &str) -> u64 {
+    let system = System::try_from(input).unwrap();
+
+    system.accepted_combinations(
+        &Target::Workflow(START.to_owned()),
+        PartRange::full()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{part1, part2};
+
+    const TEST_INPUT: &str = "px{a<2006:qkq,m>2090:A,rfg}
+pv{a>1716:R,A}
+lnx{m>1548:A,A}
+rfg{s<537:gd,x>2440:R,A}
+qs{s>3448:A,lnx}
+qkq{x<1416:A,crn}
+crn{x>2662:A,R}
+in{s<1351:px,qqz}
+qqz{s>2770:qs,m<1801:hdj,R}
+gd{a>3333:R,R}
+hdj{m>838:A,pv}
+
+{x=787,m=2655,a=1222,s=2876}
+{x=1679,m=44,a=2067,s=496}
+{x=2036,m=264,a=79,s=2244}
+{x=2461,m=1339,a=466,s=257}
+{x=2127,m=1623,a=2188,s=1013}";
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(TEST_INPUT), 19114);
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(TEST_INPUT), 167409079868000);
+    }
+}
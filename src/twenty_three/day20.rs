@@ -0,0 +1,306 @@
+use crate::Solution;
+use std::collections::{HashMap, VecDeque};
+use num::integer::lcm;
+
+pub const SOLUTION: Solution<usize, usize> = Solution { part1, part2 };
+
+const BROADCASTER: &str = "broadcaster";
+const BUTTON: &str = "button";
+const OUTPUT: &str = "rx";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Pulse {
+    Low,
+    High,
+}
+
+struct Signal<'a> {
+    src: &'a str,
+    dst: &'a str,
+    pulse: Pulse,
+}
+
+enum ModuleKind<'a> {
+    Broadcaster,
+    FlipFlop { on: bool },
+    Conjunction { memory: HashMap<&'a str, Pulse> },
+}
+
+struct Module<'a> {
+    kind: ModuleKind<'a>,
+    outputs: Vec<&'a str>,
+}
+
+impl<'a> Module<'a> {
+    /// Processes an incoming pulse, returning the pulse to send to all
+    /// outputs, if any.
+    fn receive(&mut self, src: &'a str, pulse: Pulse) -> Option<Pulse> {
+        match &mut self.kind {
+            ModuleKind::Broadcaster => Some(pulse),
+            ModuleKind::FlipFlop { on } => match pulse {
+                Pulse::High => None,
+                Pulse::Low => {
+                    *on = !*on;
+
+                    Some(if *on { Pulse::High } else { Pulse::Low })
+                },
+            },
+            ModuleKind::Conjunction { memory } => {
+                memory.insert(src, pulse);
+
+                if memory.values().all(|p| *p == Pulse::High) {
+                    Some(Pulse::Low)
+                }
+                else {
+                    Some(Pulse::High)
+                }
+            },
+        }
+    }
+}
+
+pub struct Network<'a> {
+    modules: HashMap<&'a str, Module<'a>>,
+}
+
+impl<'a> Network<'a> {
+    fn inputs(&self, module: &str) -> Vec<&'a str> {
+        self
+            .modules
+            .iter()
+            .filter(|(_, m)| m.outputs.contains(&module))
+            .map(|(label, _)| *label)
+            .collect()
+    }
+
+    /// Pushes the button once, propagating pulses in the order they were
+    /// sent until the network settles. Every signal is passed to `observe`.
+    fn press<F>(&mut self, mut observe: F) where F: FnMut(&Signal) {
+        let mut queue = VecDeque::from([Signal {
+            src: BUTTON,
+            dst: BROADCASTER,
+            pulse: Pulse::Low,
+        }]);
+
+        while let Some(signal) = queue.pop_front() {
+            observe(&signal);
+
+            let Some(module) = self.modules.get_mut(signal.dst) else {
+                continue;
+            };
+
+            if let Some(pulse) = module.receive(signal.src, signal.pulse) {
+                for dst in &module.outputs {
+                    queue.push_back(Signal { src: signal.dst, dst, pulse });
+                }
+            }
+        }
+    }
+
+    /// Turns all flip-flops off and makes all conjunctions remember a low
+    /// pulse from each of their inputs.
+    fn reset(&mut self) {
+        let inputs: HashMap<_, _> = self
+            .modules
+            .keys()
+            .map(|label| (*label, self.inputs(label)))
+            .collect();
+
+        for (label, module) in self.modules.iter_mut() {
+            match &mut module.kind {
+                ModuleKind::Broadcaster => (),
+                ModuleKind::FlipFlop { on } => *on = false,
+                ModuleKind::Conjunction { memory } => {
+                    *memory = inputs[label]
+                        .iter()
+                        .map(|input| (*input, Pulse::Low))
+                        .collect();
+                },
+            }
+        }
+    }
+
+    /// Returns the number of button presses after which `module` first
+    /// sends a high pulse to `dst`, together with the number of presses
+    /// between its first and second high pulse.
+    fn high_pulse_cycle(&mut self, module: &str, dst: &str) -> (usize, usize) {
+        let mut highs = vec!();
+        let mut presses = 0;
+
+        self.reset();
+
+        while highs.len() < 2 {
+            presses += 1;
+
+            self.press(|signal| {
+                if signal.src == module
+                    && signal.dst == dst
+                    && signal.pulse == Pulse::High {
+                    highs.push(presses);
+                }
+            });
+        }
+
+        (highs[0], highs[1] - highs[0])
+    }
+
+    /// Renders the module graph in Graphviz DOT format, drawing flip-flops
+    /// as boxes and conjunctions as diamonds.
+    pub fn to_dot(&self) -> String {
+        let mut labels: Vec<_> = self.modules.keys().collect();
+        labels.sort();
+
+        let mut dot = String::from("digraph modules {\n");
+
+        for label in labels {
+            let module = &self.modules[label];
+
+            let shape = match module.kind {
+                ModuleKind::Broadcaster => "doublecircle",
+                ModuleKind::FlipFlop { .. } => "box",
+                ModuleKind::Conjunction { .. } => "diamond",
+            };
+
+            dot.push_str(&format!("    {} [shape={}];\n", label, shape));
+
+            for output in &module.outputs {
+                dot.push_str(&format!("    {} -> {};\n", label, output));
+            }
+        }
+
+        dot.push_str("}\n");
+
+        dot
+    }
+}
+
+impl<'a> TryFrom<&'a str> for Network<'a> {
+    type Error = ();
+
+    fn try_from(input: &'a str) -> Result<Self, Self::Error> {
+        let modules = input
+            .lines()
+            .map(|l| {
+                let (name, outputs_str) = l.split_once(" -> ").ok_or(())?;
+                let outputs = outputs_str.split(", ").collect();
+
+                let (label, kind) = match name.as_bytes().first() {
+                    Some(b'%') => {
+                        (&name[1..], ModuleKind::FlipFlop { on: false })
+                    },
+                    Some(b'&') => {
+                        let memory = HashMap::new();
+                        (&name[1..], ModuleKind::Conjunction { memory })
+                    },
+                    _ if name == BROADCASTER => {
+                        (name, ModuleKind::Broadcaster)
+                    },
+                    _ => return Err(()),
+                };
+
+                Ok((label, Module { kind, outputs }))
+            })
+            .collect::<Result<_, _>>()?;
+
+        let mut network = Self { modules };
+
+        // conjunctions need to know all of their inputs up front
+        network.reset();
+
+        Ok(network)
+    }
+}
+
+fn part1(input: &str) -> usize {
+    let mut network = Network::try_from(input).unwrap();
+    let (mut low, mut high) = (0, 0);
+
+    for _ in 0..1000 {
+        network.press(|signal| match signal.pulse {
+            Pulse::Low => low += 1,
+            Pulse::High => high += 1,
+        });
+    }
+
+    low * high
+}
+
+/// Returns the fewest button presses needed to deliver a low pulse to `rx`.
+/// `rx` is fed by a single conjunction, which only sends a low pulse once
+/// all of its inputs have most recently sent a high pulse. Each of those
+/// inputs turns out to pulse high on a fixed cycle starting at zero, so the
+/// answer is the lcm of the cycle lengths.
+fn part2(input: &str) -> usize {
+    let mut network = Network::try_from(input).unwrap();
+
+    let hub = network.inputs(OUTPUT);
+    assert_eq!(hub.len(), 1, "Expected a single module feeding {}", OUTPUT);
+
+    network
+        .inputs(hub[0])
+        .iter()
+        .map(|input| {
+            let (first, period) = network.high_pulse_cycle(input, hub[0]);
+            assert_eq!(first, period, "Cycle of {} has an offset", input);
+
+            period
+        })
+        .fold(1, lcm)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{part1, part2, Network};
+
+    const TEST_INPUT_1: &str = "broadcaster -> a, b, c
+%a -> b
+%b -> c
+%c -> inv
+&inv -> a";
+
+    const TEST_INPUT_2: &str = "broadcaster -> a
+%a -> inv, con
+&inv -> b
+%b -> con
+&con -> output";
+
+    // a two-bit counter: ca pulses high every 2 presses and cb every 4
+    const TEST_INPUT_3: &str = "broadcaster -> a
+%a -> b, ca
+%b -> cb
+&ca -> hub
+&cb -> hub
+&hub -> rx";
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(TEST_INPUT_1), 32000000);
+        assert_eq!(part1(TEST_INPUT_2), 11687500);
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(TEST_INPUT_3), 4);
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let network = Network::try_from(TEST_INPUT_1).unwrap();
+
+        assert_eq!(network.to_dot(), "digraph modules {
+    a [shape=box];
+    a -> b;
+    b [shape=box];
+    b -> c;
+    broadcaster [shape=doublecircle];
+    broadcaster -> a;
+    broadcaster -> b;
+    broadcaster -> c;
+    c [shape=box];
+    c -> inv;
+    inv [shape=diamond];
+    inv -> a;
+}
+");
+    }
+}
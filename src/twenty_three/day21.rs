@@ -0,0 +1,213 @@
+use crate::Solution;
+use super::day09::Sensor;
+use std::collections::{HashSet, VecDeque};
+
+pub const SOLUTION: Solution<usize, usize> = Solution { part1, part2 };
+
+const STEPS_1: usize = 64;
+const STEPS_2: usize = 26501365;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct Position {
+    x: isize,
+    y: isize,
+}
+
+impl Position {
+    fn neighbours(&self) -> [Position; 4] {
+        [
+            Self { x: self.x, y: self.y - 1 },
+            Self { x: self.x + 1, y: self.y },
+            Self { x: self.x, y: self.y + 1 },
+            Self { x: self.x - 1, y: self.y },
+        ]
+    }
+}
+
+struct Garden {
+    rows: Vec<Vec<bool>>,
+    start: Position,
+    tiled: bool,
+}
+
+impl Garden {
+    fn width(&self) -> usize {
+        if self.rows.is_empty() {
+            return 0;
+        }
+
+        self.rows[0].len()
+    }
+
+    fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    fn is_plot(&self, pos: &Position) -> bool {
+        let (width, height) = (self.width() as isize, self.height() as isize);
+
+        if !self.tiled
+            && (pos.x < 0 || pos.x >= width || pos.y < 0 || pos.y >= height) {
+            return false;
+        }
+
+        let (x, y) = (pos.x.rem_euclid(width), pos.y.rem_euclid(height));
+
+        self.rows[y as usize][x as usize]
+    }
+
+    /// Returns the number of garden plots the elf can end up on after
+    /// exactly `steps` steps. Any plot reached earlier with matching parity
+    /// counts too, since the elf can step back and forth to use up the
+    /// remaining steps.
+    fn reachable(&self, steps: usize) -> usize {
+        let mut visited = HashSet::from([self.start]);
+        let mut queue = VecDeque::from([(self.start, 0)]);
+        let mut count = 0;
+
+        while let Some((pos, distance)) = queue.pop_front() {
+            if distance % 2 == steps % 2 {
+                count += 1;
+            }
+
+            if distance == steps {
+                continue;
+            }
+
+            for next in pos.neighbours() {
+                if self.is_plot(&next) && visited.insert(next) {
+                    queue.push_back((next, distance + 1));
+                }
+            }
+        }
+
+        count
+    }
+
+    /// Returns the number of reachable plots on an infinitely tiled garden
+    /// without walking all of it. Once the reachable diamond spans whole
+    /// tiles, the count grows quadratically in the number of tiles crossed,
+    /// so three samples taken a tile apart determine all later values.
+    fn reachable_extrapolated(&self, steps: usize) -> usize {
+        let width = self.width();
+        let (tiles, remainder) = (steps / width, steps % width);
+
+        let samples: Vec<_> = (0..3)
+            .map(|i| self.reachable(remainder + i * width) as isize)
+            .collect();
+
+        if tiles < samples.len() {
+            return samples[tiles] as usize;
+        }
+
+        let ahead = tiles + 1 - samples.len();
+
+        Sensor { readings: samples }
+            .fit_degree(2)
+            .unwrap()
+            .predict(ahead)
+            .unwrap() as usize
+    }
+}
+
+impl TryFrom<&str> for Garden {
+    type Error = ();
+
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        let mut start = None;
+
+        let rows = input
+            .lines()
+            .enumerate()
+            .map(|(y, l)| l
+                .chars()
+                .enumerate()
+                .map(|(x, c)| match c {
+                    '.' => Ok(true),
+                    '#' => Ok(false),
+                    'S' => {
+                        let (x, y) = (x as isize, y as isize);
+                        start = Some(Position { x, y });
+                        Ok(true)
+                    },
+                    _ => Err(()),
+                })
+                .collect()
+            )
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { rows, start: start.ok_or(())?, tiled: false })
+    }
+}
+
+fn part1(input: &str) -> usize {
+    let garden = Garden::try_from(input).unwrap();
+
+    garden.reachable(STEPS_1)
+}
+
+fn part2(input: &str) -> usize {
+    let mut garden = Garden::try_from(input).unwrap();
+    garden.tiled = true;
+
+    garden.reachable_extrapolated(STEPS_2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Garden;
+
+    const TEST_INPUT: &str = "...........
+.....###.#.
+.###.##..#.
+..#.#...#..
+....#.#....
+.##..S####.
+.##..#...#.
+.......##..
+.##.#.####.
+.##..##.##.
+...........";
+
+    // unlike the example, this garden has clear lines through its start
+    // position, which the extrapolation relies on
+    const TEST_INPUT_OPEN: &str = "...........
+.........#.
+.##.....#..
+..#.....#..
+....#.#....
+.....S.....
+.#.....#.#.
+.......##..
+.##.#..##..
+.#.....#.#.
+...........";
+
+    #[test]
+    fn test_part1() {
+        let garden = Garden::try_from(TEST_INPUT).unwrap();
+
+        assert_eq!(garden.reachable(6), 16);
+    }
+
+    #[test]
+    fn test_part2() {
+        let mut garden = Garden::try_from(TEST_INPUT).unwrap();
+        garden.tiled = true;
+
+        assert_eq!(garden.reachable(6), 16);
+        assert_eq!(garden.reachable(10), 50);
+        assert_eq!(garden.reachable(50), 1594);
+        assert_eq!(garden.reachable(100), 6536);
+
+        let mut garden = Garden::try_from(TEST_INPUT_OPEN).unwrap();
+        garden.tiled = true;
+
+        for steps in [5, 27, 60, 115] {
+            assert_eq!(
+                garden.reachable_extrapolated(steps),
+                garden.reachable(steps)
+            );
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use crate::Solution;
+use std::collections::HashMap;
+
+pub const SOLUTION: Solution<usize, usize> = Solution { part1, part2 };
+
+#[derive(Clone, Copy)]
+struct Brick {
+    min: [usize; 3],
+    max: [usize; 3],
+}
+
+impl Brick {
+    fn footprint(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (self.min[0]..=self.max[0])
+            .flat_map(|x| (self.min[1]..=self.max[1]).map(move |y| (x, y)))
+    }
+
+    fn height(&self) -> usize {
+        self.max[2] - self.min[2] + 1
+    }
+}
+
+impl TryFrom<&str> for Brick {
+    type Error = ();
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        let (l, r) = s.split_once('~').ok_or(())?;
+
+        let parse = |coords: &str| -> Result<[usize; 3], ()> {
+            coords
+                .split(',')
+                .map(|n| n.parse::<usize>().or(Err(())))
+                .collect::<Result<Vec<_>, _>>()?
+                .try_into()
+                .or(Err(()))
+        };
+
+        let (a, b) = (parse(l)?, parse(r)?);
+
+        let min = [a[0].min(b[0]), a[1].min(b[1]), a[2].min(b[2])];
+        let max = [a[0].max(b[0]), a[1].max(b[1]), a[2].max(b[2])];
+
+        Ok(Self { min, max })
+    }
+}
+
+/// The settled pile of bricks. Bricks are indexed in the order they came to
+/// rest, so every brick's supporters have a lower index than the brick
+/// itself.
+struct Tower {
+    supports: Vec<Vec<usize>>,
+    supported_by: Vec<Vec<usize>>,
+}
+
+impl Tower {
+    /// Drops all bricks onto the ground, tracking the highest brick over
+    /// each column in a height map.
+    fn settle(mut bricks: Vec<Brick>) -> Self {
+        bricks.sort_by_key(|brick| brick.min[2]);
+
+        let mut height_map: HashMap<(usize, usize), (usize, usize)> =
+            HashMap::new();
+        let mut supports = vec![vec!(); bricks.len()];
+        let mut supported_by = vec![vec!(); bricks.len()];
+
+        for (id, brick) in bricks.iter().enumerate() {
+            let rest = brick
+                .footprint()
+                .filter_map(|cell| height_map.get(&cell))
+                .map(|(top, _)| *top)
+                .max()
+                .unwrap_or(0);
+
+            let mut supporters: Vec<_> = brick
+                .footprint()
+                .filter_map(|cell| height_map.get(&cell))
+                .filter(|(top, _)| *top == rest)
+                .map(|(_, supporter)| *supporter)
+                .collect();
+
+            supporters.sort();
+            supporters.dedup();
+
+            for supporter in &supporters {
+                supports[*supporter].push(id);
+            }
+
+            supported_by[id] = supporters;
+
+            for cell in brick.footprint() {
+                height_map.insert(cell, (rest + brick.height(), id));
+            }
+        }
+
+        Self { supports, supported_by }
+    }
+
+    fn len(&self) -> usize {
+        self.supports.len()
+    }
+
+    /// Returns whether removing the brick leaves every brick above it with
+    /// at least one other supporter.
+    fn disintegrable(&self, brick: usize) -> bool {
+        self
+            .supports[brick]
+            .iter()
+            .all(|above| self.supported_by[*above].len() > 1)
+    }
+
+    /// Returns the number of other bricks that fall when each brick is
+    /// disintegrated. A brick falls exactly when every path from the ground
+    /// up to it runs through the disintegrated brick, i.e. when that brick
+    /// dominates it. Since bricks are in topological order, each brick's
+    /// immediate dominator is the nearest common dominator of its
+    /// supporters, and the fall count is the size of its dominator subtree.
+    fn chain_reactions(&self) -> Vec<usize> {
+        let ground = self.len();
+
+        let mut idom = vec![ground; self.len() + 1];
+        let mut depth = vec![0; self.len() + 1];
+
+        for brick in 0..self.len() {
+            let dominator = self
+                .supported_by[brick]
+                .iter()
+                .copied()
+                .reduce(|mut a, mut b| {
+                    while a != b {
+                        if depth[a] >= depth[b] {
+                            a = idom[a];
+                        }
+                        else {
+                            b = idom[b];
+                        }
+                    }
+
+                    a
+                })
+                .unwrap_or(ground);
+
+            idom[brick] = dominator;
+            depth[brick] = depth[dominator] + 1;
+        }
+
+        let mut subtree = vec![1; self.len() + 1];
+
+        for brick in (0..self.len()).rev() {
+            subtree[idom[brick]] += subtree[brick];
+        }
+
+        subtree
+            .iter()
+            .take(self.len())
+            .map(|size| size - 1)
+            .collect()
+    }
+}
+
+impl TryFrom<&str> for Tower {
+    type Error = ();
+
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        let bricks = input
+            .lines()
+            .map(Brick::try_from)
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self::settle(bricks))
+    }
+}
+
+fn part1(input: &str) -> usize {
+    let tower = Tower::try_from(input).unwrap();
+
+    (0..tower.len())
+        .filter(|brick| tower.disintegrable(*brick))
+        .count()
+}
+
+fn part2(input: &str) -> usize {
+    let tower = Tower::try_from(input).unwrap();
+
+    tower.chain_reactions().iter().sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{part1, part2};
+
+    const TEST_INPUT: &str = "1,0,1~1,2,1
+0,0,2~2,0,2
+0,2,3~2,2,3
+0,0,4~0,2,4
+2,0,5~2,2,5
+0,1,6~2,1,6
+1,1,8~1,1,9";
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(TEST_INPUT), 5);
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(TEST_INPUT), 7);
+    }
+}
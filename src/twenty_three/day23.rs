@@ -0,0 +1,268 @@
+use crate::Solution;
+use std::collections::HashMap;
+
+pub const SOLUTION: Solution<usize, usize> = Solution { part1, part2 };
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+use Direction::{*};
+
+const DIRECTIONS: [Direction; 4] = [North, East, South, West];
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct Position {
+    x: isize,
+    y: isize,
+}
+
+impl Position {
+    fn neighbour(&self, dir: Direction) -> Self {
+        let (x, y) = match dir {
+            North => (self.x, self.y - 1),
+            East => (self.x + 1, self.y),
+            South => (self.x, self.y + 1),
+            West => (self.x - 1, self.y),
+        };
+
+        Self { x, y }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Tile {
+    Path,
+    Forest,
+    Slope(Direction),
+}
+
+impl TryFrom<char> for Tile {
+    type Error = ();
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            '.' => Ok(Tile::Path),
+            '#' => Ok(Tile::Forest),
+            '^' => Ok(Tile::Slope(North)),
+            '>' => Ok(Tile::Slope(East)),
+            'v' => Ok(Tile::Slope(South)),
+            '<' => Ok(Tile::Slope(West)),
+            _ => Err(()),
+        }
+    }
+}
+
+/// The trail map reduced to its junctions, with edges weighted by the
+/// length of the corridor between them.
+struct Graph {
+    edges: Vec<Vec<(usize, usize)>>,
+    start: usize,
+    end: usize,
+}
+
+impl Graph {
+    fn dfs(&self, node: usize, visited: u64, distance: usize) -> Option<usize> {
+        if node == self.end {
+            return Some(distance);
+        }
+
+        self
+            .edges[node]
+            .iter()
+            .filter(|(next, _)| visited & (1 << next) == 0)
+            .filter_map(|(next, weight)| {
+                self.dfs(*next, visited | (1 << next), distance + weight)
+            })
+            .max()
+    }
+
+    /// Returns the length of the longest path from start to end that visits
+    /// no junction twice, if any.
+    fn longest_path(&self) -> Option<usize> {
+        assert!(self.edges.len() <= 64, "Too many junctions for a bitmask");
+
+        self.dfs(self.start, 1 << self.start, 0)
+    }
+}
+
+struct Trails {
+    rows: Vec<Vec<Tile>>,
+    start: Position,
+    end: Position,
+}
+
+impl Trails {
+    fn get(&self, pos: &Position) -> Tile {
+        if pos.y < 0 || pos.y as usize >= self.rows.len() {
+            return Tile::Forest;
+        }
+
+        let row = &self.rows[pos.y as usize];
+
+        if pos.x < 0 || pos.x as usize >= row.len() {
+            return Tile::Forest;
+        }
+
+        row[pos.x as usize]
+    }
+
+    fn is_junction(&self, pos: &Position) -> bool {
+        *pos == self.start || *pos == self.end || DIRECTIONS
+            .iter()
+            .filter(|dir| self.get(&pos.neighbour(**dir)) != Tile::Forest)
+            .count() > 2
+    }
+
+    /// Returns the positions reachable in one step from `pos`. On slippery
+    /// slopes, the only way to go is downhill.
+    fn steps(&self, pos: &Position, slippery: bool) -> Vec<Position> {
+        let dirs = match self.get(pos) {
+            Tile::Slope(dir) if slippery => vec!(dir),
+            _ => DIRECTIONS.to_vec(),
+        };
+
+        dirs
+            .iter()
+            .map(|dir| pos.neighbour(*dir))
+            .filter(|next| self.get(next) != Tile::Forest)
+            .collect()
+    }
+
+    /// Follows the corridor from `junction` through `first` until the next
+    /// junction, returning it along with the number of steps taken.
+    fn follow(
+        &self,
+        junction: Position,
+        first: Position,
+        slippery: bool,
+    ) -> Option<(Position, usize)> {
+        let (mut prev, mut current) = (junction, first);
+        let mut distance = 1;
+
+        while !self.is_junction(&current) {
+            let next = self
+                .steps(&current, slippery)
+                .into_iter()
+                .find(|next| *next != prev)?;
+
+            (prev, current) = (current, next);
+            distance += 1;
+        }
+
+        Some((current, distance))
+    }
+
+    fn compress(&self, slippery: bool) -> Graph {
+        let junctions: Vec<_> = self
+            .rows
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| (0..row.len())
+                .map(move |x| Position { x: x as isize, y: y as isize })
+            )
+            .filter(|pos| self.get(pos) != Tile::Forest)
+            .filter(|pos| self.is_junction(pos))
+            .collect();
+
+        let index: HashMap<_, _> = junctions
+            .iter()
+            .enumerate()
+            .map(|(i, pos)| (*pos, i))
+            .collect();
+
+        let edges = junctions
+            .iter()
+            .map(|junction| self
+                .steps(junction, slippery)
+                .into_iter()
+                .filter_map(|first| self.follow(*junction, first, slippery))
+                .filter(|(next, _)| next != junction)
+                .map(|(next, distance)| (index[&next], distance))
+                .collect()
+            )
+            .collect();
+
+        Graph { edges, start: index[&self.start], end: index[&self.end] }
+    }
+}
+
+impl TryFrom<&str> for Trails {
+    type Error = ();
+
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        let rows: Vec<Vec<_>> = input
+            .lines()
+            .map(|l| l.chars().map(Tile::try_from).collect())
+            .collect::<Result<_, _>>()?;
+
+        let find_path = |y: usize| -> Result<Position, ()> {
+            let x = rows
+                .get(y)
+                .and_then(|row| row.iter().position(|t| *t == Tile::Path))
+                .ok_or(())?;
+
+            Ok(Position { x: x as isize, y: y as isize })
+        };
+
+        let start = find_path(0)?;
+        let end = find_path(rows.len().saturating_sub(1))?;
+
+        Ok(Self { rows, start, end })
+    }
+}
+
+fn part1(input: &str) -> usize {
+    let trails = Trails::try_from(input).unwrap();
+
+    trails.compress(true).longest_path().unwrap()
+}
+
+fn part2(input: &str) -> usize {
+    let trails = Trails::try_from(input).unwrap();
+
+    trails.compress(false).longest_path().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{part1, part2};
+
+    const TEST_INPUT: &str = "#.#####################
+#.......#########...###
+#######.#########.#.###
+###.....#.>.>.###.#.###
+###v#####.#v#.###.#.###
+###.>...#.#.#.....#...#
+###v###.#.#.#########.#
+###...#.#.#.......#...#
+#####.#.#.#######.#.###
+#.....#.#.#.......#...#
+#.#####.#.#.#########v#
+#.#...#...#...###...>.#
+#.#.#v#######v###.###v#
+#...#.>.#...>.>.#.###.#
+#####v#.#.###v#.#.###.#
+#.....#...#...#.#.#...#
+#.#########.###.#.#.###
+#...###...#...#...#.###
+###.###.#.###v#####v###
+#...#...#.#.>.>.#.>.###
+#.###.###.#.###.#.#v###
+#.....###...###...#...#
+#####################.#";
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(TEST_INPUT), 94);
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(TEST_INPUT), 154);
+    }
+}
@@ -0,0 +1,243 @@
+use crate::Solution;
+use num::{BigInt, BigRational, Signed, ToPrimitive, Zero};
+
+pub const SOLUTION: Solution<usize, i64> = Solution { part1, part2 };
+
+const TEST_AREA_MIN: i64 = 200000000000000;
+const TEST_AREA_MAX: i64 = 400000000000000;
+
+type Vector = [BigInt; 3];
+
+fn cross(a: &Vector, b: &Vector) -> Vector {
+    [
+        &a[1] * &b[2] - &a[2] * &b[1],
+        &a[2] * &b[0] - &a[0] * &b[2],
+        &a[0] * &b[1] - &a[1] * &b[0],
+    ]
+}
+
+fn sub(a: &Vector, b: &Vector) -> Vector {
+    [&a[0] - &b[0], &a[1] - &b[1], &a[2] - &b[2]]
+}
+
+struct Hailstone {
+    pos: Vector,
+    vel: Vector,
+}
+
+impl Hailstone {
+    /// Returns where the paths of two hailstones cross in the xy-plane, if
+    /// they do so in the future for both of them. Parallel paths are never
+    /// considered to cross.
+    fn crossing_xy(&self, other: &Self) -> Option<(BigRational, BigRational)> {
+        let (p, v) = (&self.pos, &self.vel);
+        let (q, w) = (&other.pos, &other.vel);
+
+        let det = &v[0] * &w[1] - &v[1] * &w[0];
+
+        if det.is_zero() {
+            return None;
+        }
+
+        let (dx, dy) = (&q[0] - &p[0], &q[1] - &p[1]);
+
+        let t = BigRational::new(&dx * &w[1] - &dy * &w[0], det.clone());
+        let s = BigRational::new(&dx * &v[1] - &dy * &v[0], det);
+
+        if t.is_negative() || s.is_negative() {
+            return None;
+        }
+
+        let x = BigRational::from_integer(p[0].clone())
+            + &t * BigRational::from_integer(v[0].clone());
+        let y = BigRational::from_integer(p[1].clone())
+            + &t * BigRational::from_integer(v[1].clone());
+
+        Some((x, y))
+    }
+}
+
+impl TryFrom<&str> for Hailstone {
+    type Error = ();
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        let (pos_str, vel_str) = s.split_once('@').ok_or(())?;
+
+        let parse = |v: &str| -> Result<Vector, ()> {
+            v
+                .split(',')
+                .map(|n| n.trim().parse::<BigInt>().or(Err(())))
+                .collect::<Result<Vec<_>, _>>()?
+                .try_into()
+                .or(Err(()))
+        };
+
+        Ok(Self { pos: parse(pos_str)?, vel: parse(vel_str)? })
+    }
+}
+
+/// Solves the square linear system `a * x = b` exactly by Gaussian
+/// elimination, returning `None` if the system is singular.
+fn solve(
+    mut a: Vec<Vec<BigRational>>,
+    mut b: Vec<BigRational>,
+) -> Option<Vec<BigRational>> {
+    let n = b.len();
+
+    for col in 0..n {
+        let pivot = (col..n).find(|row| !a[*row][col].is_zero())?;
+
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+
+        let pivot_row = a[col].clone();
+
+        for row in 0..n {
+            if row == col || a[row][col].is_zero() {
+                continue;
+            }
+
+            let factor = &a[row][col] / &pivot_row[col];
+
+            for (e, p) in a[row][col..].iter_mut().zip(&pivot_row[col..]) {
+                *e -= &factor * p;
+            }
+
+            let delta = &factor * &b[col];
+            b[row] -= delta;
+        }
+    }
+
+    Some((0..n).map(|i| &b[i] / &a[i][i]).collect())
+}
+
+struct Hailstorm {
+    hailstones: Vec<Hailstone>,
+}
+
+impl Hailstorm {
+    fn crossings_within(&self, min: i64, max: i64) -> usize {
+        let min = BigRational::from_integer(min.into());
+        let max = BigRational::from_integer(max.into());
+        let in_area = |c: &BigRational| *c >= min && *c <= max;
+
+        self
+            .hailstones
+            .iter()
+            .enumerate()
+            .flat_map(|(i, a)| self
+                .hailstones[i + 1..]
+                .iter()
+                .map(move |b| (a, b))
+            )
+            .filter_map(|(a, b)| a.crossing_xy(b))
+            .filter(|(x, y)| in_area(x) && in_area(y))
+            .count()
+    }
+
+    /// Returns the position and velocity of a rock thrown so that it hits
+    /// every hailstone. For the rock at P moving at V to hit hailstone i,
+    /// (P - p_i) x (V - v_i) = 0. The quadratic term P x V is shared by all
+    /// hailstones, so subtracting the equations of two hailstones leaves
+    ///
+    ///   P x (v_i - v_j) + (p_i - p_j) x V = p_i x v_i - p_j x v_j
+    ///
+    /// which is linear in P and V. Two such pairs give six equations for the
+    /// six unknowns.
+    fn rock(&self) -> Option<(Vector, Vector)> {
+        let equations = |i: usize, j: usize| {
+            let (a, b) = (&self.hailstones[i], &self.hailstones[j]);
+
+            let w = sub(&a.vel, &b.vel);
+            let u = sub(&a.pos, &b.pos);
+            let rhs = sub(&cross(&a.pos, &a.vel), &cross(&b.pos, &b.vel));
+
+            let [wx, wy, wz] = w;
+            let [ux, uy, uz] = u;
+            let zero = BigInt::zero;
+
+            // unknowns are ordered as [Px, Py, Pz, Vx, Vy, Vz]
+            let rows = [
+                [zero(), wz.clone(), -&wy, zero(), -&uz, uy.clone()],
+                [-&wz, zero(), wx.clone(), uz, zero(), -&ux],
+                [wy, -wx, zero(), -uy, ux, zero()],
+            ];
+
+            rows.into_iter().zip(rhs)
+        };
+
+        // some triples of hailstones give a singular system, so try others
+        // until one pins down the rock
+        (2..self.hailstones.len()).find_map(|k| {
+            let (a, b): (Vec<_>, Vec<_>) = equations(0, 1)
+                .chain(equations(0, k))
+                .map(|(row, rhs)| (
+                    row.map(BigRational::from_integer).to_vec(),
+                    BigRational::from_integer(rhs),
+                ))
+                .unzip();
+
+            let x = solve(a, b)?;
+
+            if !x.iter().all(|e| e.is_integer()) {
+                return None;
+            }
+
+            let x: Vec<_> = x.into_iter().map(|e| e.to_integer()).collect();
+
+            Some((
+                [x[0].clone(), x[1].clone(), x[2].clone()],
+                [x[3].clone(), x[4].clone(), x[5].clone()],
+            ))
+        })
+    }
+}
+
+impl TryFrom<&str> for Hailstorm {
+    type Error = ();
+
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        let hailstones = input
+            .lines()
+            .map(Hailstone::try_from)
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { hailstones })
+    }
+}
+
+fn part1(input: &str) -> usize {
+    let hailstorm = Hailstorm::try_from(input).unwrap();
+
+    hailstorm.crossings_within(TEST_AREA_MIN, TEST_AREA_MAX)
+}
+
+fn part2(input: &str) -> i64 {
+    let hailstorm = Hailstorm::try_from(input).unwrap();
+    let (pos, _) = hailstorm.rock().unwrap();
+
+    pos.iter().sum::<BigInt>().to_i64().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{part2, Hailstorm};
+
+    const TEST_INPUT: &str = "19, 13, 30 @ -2,  1, -2
+18, 19, 22 @ -1, -1, -2
+20, 25, 34 @ -2, -2, -4
+12, 31, 28 @ -1, -2, -1
+20, 19, 15 @  1, -5, -3";
+
+    #[test]
+    fn test_part1() {
+        let hailstorm = Hailstorm::try_from(TEST_INPUT).unwrap();
+
+        assert_eq!(hailstorm.crossings_within(7, 27), 2);
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(TEST_INPUT), 47);
+    }
+}
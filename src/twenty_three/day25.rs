@@ -0,0 +1,95 @@
+use crate::Solution;
+use crate::graph::Graph;
+use std::collections::HashMap;
+
+pub const SOLUTION: Solution<usize, usize> = Solution { part1, part2 };
+
+const CUT_SIZE: usize = 3;
+
+struct Apparatus {
+    graph: Graph,
+}
+
+impl Apparatus {
+    /// Returns the sizes of the two groups of components left after cutting
+    /// exactly three wires. Any node ends up on one side of the cut, so
+    /// pairing it with every other node eventually finds one across it.
+    fn split(&self) -> Option<(usize, usize)> {
+        (1..self.graph.len()).find_map(|sink| {
+            match self.graph.min_cut(0, sink, CUT_SIZE)? {
+                (CUT_SIZE, side) => {
+                    Some((side.len(), self.graph.len() - side.len()))
+                },
+                _ => None,
+            }
+        })
+    }
+}
+
+impl TryFrom<&str> for Apparatus {
+    type Error = ();
+
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        let mut ids: HashMap<&str, usize> = HashMap::new();
+        let mut wires = vec!();
+
+        for line in input.lines() {
+            let (component, others) = line.split_once(": ").ok_or(())?;
+
+            for other in others.split_whitespace() {
+                wires.push((component, other));
+            }
+        }
+
+        for (a, b) in &wires {
+            for label in [a, b] {
+                let next_id = ids.len();
+                ids.entry(label).or_insert(next_id);
+            }
+        }
+
+        let mut graph = Graph::new(ids.len());
+
+        for (a, b) in wires {
+            graph.add_edge(ids[a], ids[b]);
+        }
+
+        Ok(Self { graph })
+    }
+}
+
+fn part1(input: &str) -> usize {
+    let apparatus = Apparatus::try_from(input).unwrap();
+    let (l, r) = apparatus.split().unwrap();
+
+    l * r
+}
+
+/// Day 25 only has a single puzzle.
+fn part2(_input: &str) -> usize {
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::part1;
+
+    const TEST_INPUT: &str = "jqt: rhn xhk nvd
+rsh: frs pzl lsr
+xhk: hfx
+cmg: qnr nvd lhk bvb
+rhn: xhk bvb hfx
+bvb: xhk hfx
+pzl: lsr hfx nvd
+qnr: nvd
+ntq: jqt hfx bvb xhk
+nvd: lhk
+lsr: lhk
+rzs: qnr cmg lsr rsh
+frs: qnr lhk lsr";
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(TEST_INPUT), 54);
+    }
+}
@@ -1,14 +1,14 @@
 use crate::Solver;
 
 mod day00;
-mod day01;
-mod day02;
-mod day03;
-mod day04;
-mod day06;
-mod day07;
-mod day08;
-mod day09;
+pub mod day01;
+pub mod day02;
+pub mod day03;
+pub mod day04;
+pub mod day06;
+pub mod day07;
+pub mod day08;
+pub mod day09;
 //mod day10;
 mod day11;
 //mod day12;
@@ -16,8 +16,17 @@ mod day13;
 mod day14;
 mod day15;
 mod day16;
+mod day17;
+mod day18;
+mod day19;
+pub mod day20;
+mod day21;
+mod day22;
+mod day23;
+mod day24;
+mod day25;
 
-const SOLUTIONS: [&dyn Solver; 17] = [
+const SOLUTIONS: [&dyn Solver; 26] = [
     &day00::SOLUTION,
     &day01::SOLUTION,
     &day02::SOLUTION,
@@ -35,6 +44,15 @@ const SOLUTIONS: [&dyn Solver; 17] = [
     &day14::SOLUTION,
     &day15::SOLUTION,
     &day16::SOLUTION,
+    &day17::SOLUTION,
+    &day18::SOLUTION,
+    &day19::SOLUTION,
+    &day20::SOLUTION,
+    &day21::SOLUTION,
+    &day22::SOLUTION,
+    &day23::SOLUTION,
+    &day24::SOLUTION,
+    &day25::SOLUTION,
 ];
 
 pub fn get_solution(day: usize) -> &'static dyn Solver {